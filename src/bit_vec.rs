@@ -1,6 +1,8 @@
+use crate::error::{ensure, ensure_positive, ParameterError};
 use num_traits::{AsPrimitive, FromPrimitive, PrimInt, Unsigned};
 use std::marker::PhantomData;
 
+#[derive(Clone)]
 pub(crate) struct BitVec<T, const N: usize> {
     buf: Vec<u8>,
     size: usize,
@@ -17,25 +19,53 @@ where
     );
 
     pub fn new(size: usize) -> Self {
+        Self::try_new(size).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(size: usize) -> Result<Self, ParameterError> {
         // Add a binding to enforce a compile-time assertion.
         #[allow(clippy::let_unit_value)]
         let _ = Self::PACKED_LENGTH_OK;
 
-        assert!(size > 0, "size must be > 0");
+        ensure_positive("size", size)?;
+        ensure(
+            size.checked_mul(N).is_some(),
+            ParameterError::Overflow { name: "size" },
+        )?;
         // Allocate 1 extra byte for safe indexing byte pairs.
-        let num_bytes = (N * size).div_ceil(8) + 1;
+        let num_bytes = Self::packed_len(size) + 1;
 
-        Self {
+        Ok(Self {
             buf: vec![0; num_bytes],
             size,
             _phantom: PhantomData,
-        }
+        })
+    }
+
+    pub fn from_bytes(size: usize, bytes: &[u8]) -> Self {
+        let mut bit_vec = Self::new(size);
+        assert_eq!(
+            bytes.len(),
+            Self::packed_len(size),
+            "bytes must hold exactly size words"
+        );
+        bit_vec.buf[..bytes.len()].copy_from_slice(bytes);
+        bit_vec
+    }
+
+    pub fn packed_len(size: usize) -> usize {
+        (N * size).div_ceil(8)
     }
 
     pub fn size(&self) -> usize {
         self.size
     }
 
+    pub fn as_bytes(&self) -> &[u8] {
+        // Skip the extra byte used for safe indexing.
+        &self.buf[..self.buf.len() - 1]
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
         (0..self.size).map(move |index| {
             // SAFETY: `index` is bound by the size of vec
@@ -173,6 +203,30 @@ mod tests {
         assert_eq!(BitVec::<u32, 20>::new(8).buf.len(), 21);
     }
 
+    #[test]
+    fn test_try_new() {
+        assert_eq!(
+            BitVec::<u8, 5>::try_new(0).err(),
+            Some(ParameterError::NotGreaterThan {
+                name: "size",
+                bound: 0
+            })
+        );
+        assert_eq!(
+            BitVec::<u32, 20>::try_new(usize::MAX).err(),
+            Some(ParameterError::Overflow { name: "size" })
+        );
+    }
+
+    #[test]
+    fn test_bytes_roundtrip() {
+        let bv = make_bit_vec_u32();
+        let decoded = BitVec::<u32, 17>::from_bytes(5, bv.as_bytes());
+
+        assert_eq!(bv.as_bytes().len(), 11);
+        assert_eq!(decoded.buf, bv.buf);
+    }
+
     #[test]
     fn test_get_u8() {
         let bv = make_bit_vec_u8();
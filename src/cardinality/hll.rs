@@ -1,9 +1,65 @@
 use crate::bit_vec::BitVec;
+use crate::cardinality::hll_joint::JointStatistics;
 use crate::cardinality::Cardinality;
+use crate::encoding::{decode_exact, DecodeError, Decoder, Encoder, Kind};
+use crate::error::{ensure, ParameterError};
+use crate::merge::{ensure_same_hasher, IncompatibleSketch, Merge};
+use std::borrow::Cow;
 use std::fmt::{Debug, Formatter};
 use std::hash::{BuildHasher, Hash};
+use std::io::{Read, Write};
 use std::marker::PhantomData;
 
+// Intersection of two sketches with the sizes of the two differences, from the joint
+// maximum-likelihood estimate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IntersectionEstimate {
+    pub intersection: f64,
+    // Standard error of `intersection` from the observed Fisher information.
+    pub std_error: f64,
+    pub only_self: f64,
+    pub only_other: f64,
+}
+
+impl IntersectionEstimate {
+    pub fn union(&self) -> f64 {
+        self.intersection + self.only_self + self.only_other
+    }
+
+    pub fn jaccard(&self) -> f64 {
+        let union = self.union();
+        if union > 0. {
+            self.intersection / union
+        } else {
+            0.
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Estimator {
+    // Flajolet et al., with linear counting for small and the 2^32 correction for large
+    // cardinalities. This is what `Cardinality::count` returns.
+    #[default]
+    Classic,
+    // Ertl, "New cardinality estimation algorithms for HyperLogLog sketches", section 4.
+    // Corrects for empty and saturated registers from the register histogram, without
+    // thresholds or empirical bias tables.
+    Improved,
+}
+
+pub(crate) fn alpha(m: usize) -> f64 {
+    if m >= 128 {
+        0.7213 / (1. + 1.079 / m as f64)
+    } else if m == 64 {
+        0.709
+    } else if m == 32 {
+        0.697
+    } else {
+        0.673
+    }
+}
+
 pub struct HyperLogLog<T, H> {
     registers: BitVec<u8, 6>,
     precision: usize,
@@ -13,43 +69,184 @@ pub struct HyperLogLog<T, H> {
 
 impl<T, H> HyperLogLog<T, H> {
     pub fn new(precision: usize, build_hasher: H) -> Self {
-        assert!(
+        Self::try_new(precision, build_hasher).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(precision: usize, build_hasher: H) -> Result<Self, ParameterError> {
+        ensure(
             (4..=18).contains(&precision),
-            "precision must be in the range [4, 18]"
-        );
-        Self {
-            registers: BitVec::new(1 << precision),
+            ParameterError::OutOfRange {
+                name: "precision",
+                range: "[4, 18]",
+            },
+        )?;
+        Ok(Self {
+            registers: BitVec::try_new(1 << precision)?,
             precision,
             build_hasher,
             _phantom: PhantomData,
-        }
+        })
     }
 
     pub fn with_error(epsilon: f64, build_hasher: H) -> Self {
-        assert!(
+        Self::try_with_error(epsilon, build_hasher).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_with_error(epsilon: f64, build_hasher: H) -> Result<Self, ParameterError> {
+        ensure(
             0.0 < epsilon && epsilon < 1.0,
-            "epsilon must be in the range (0, 1)"
-        );
+            ParameterError::OutOfRange {
+                name: "epsilon",
+                range: "(0, 1)",
+            },
+        )?;
         let m = (1.04 / epsilon).powi(2);
         let precision = m.log2().ceil() as usize;
-        Self::new(precision, build_hasher)
+        Self::try_new(precision, build_hasher)
     }
 
     pub fn precision(&self) -> usize {
         self.precision
     }
 
-    fn alpha(&self) -> f64 {
-        let m = self.registers.size();
-        if m >= 128 {
-            0.7213 / (1. + 1.079 / m as f64)
-        } else if m == 64 {
-            0.709
-        } else if m == 32 {
-            0.697
+    // The register of index `i` at the lower precision `p` is the maximum over the registers
+    // whose index starts with the bits of `i`. Their remaining index bits become the leading
+    // bits of the hash suffix, so rho is recomputed from them.
+    fn folded_registers(&self, precision: usize) -> BitVec<u8, 6> {
+        let dropped_bits = self.precision - precision;
+        let mut registers = BitVec::new(1 << precision);
+        for (index, register) in self.registers.iter().enumerate() {
+            if register == 0 {
+                continue;
+            }
+            let dropped = index & ((1 << dropped_bits) - 1);
+            let rho = if dropped == 0 {
+                register + dropped_bits as u8
+            } else {
+                (dropped.leading_zeros() as usize - (usize::BITS as usize - dropped_bits)) as u8 + 1
+            };
+            let index = index >> dropped_bits;
+            if registers.get(index) < rho {
+                registers.set(index, rho);
+            }
+        }
+        registers
+    }
+
+    // The registers folded to `precision` if it is lower than the sketch's own.
+    fn registers_at(&self, precision: usize) -> Cow<'_, BitVec<u8, 6>> {
+        if precision < self.precision {
+            Cow::Owned(self.folded_registers(precision))
         } else {
-            0.673
+            Cow::Borrowed(&self.registers)
+        }
+    }
+}
+
+impl<T, H> HyperLogLog<T, H>
+where
+    H: Clone,
+{
+    pub fn reduce_precision(&self, precision: usize) -> Self {
+        self.try_reduce_precision(precision)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_reduce_precision(&self, precision: usize) -> Result<Self, ParameterError> {
+        ensure(
+            (4..=self.precision).contains(&precision),
+            ParameterError::OutOfRange {
+                name: "precision",
+                range: "[4, current precision]",
+            },
+        )?;
+        Ok(Self {
+            registers: self.folded_registers(precision),
+            precision,
+            build_hasher: self.build_hasher.clone(),
+            _phantom: PhantomData,
+        })
+    }
+}
+
+impl<T, H> HyperLogLog<T, H>
+where
+    H: BuildHasher,
+{
+    pub fn to_bytes(&self) -> Vec<u8> {
+        Encoder::new(Kind::HyperLogLog)
+            .put_u64(self.precision as u64)
+            .put_hasher(&self.build_hasher)
+            .finish(self.registers.as_bytes())
+    }
+
+    pub fn write_to<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+        writer.write_all(&self.to_bytes())
+    }
+
+    pub fn from_bytes(bytes: &[u8], build_hasher: H) -> Result<Self, DecodeError> {
+        decode_exact(bytes, |reader| Self::read_from(reader, build_hasher))
+    }
+
+    pub fn read_from<R: Read>(reader: R, build_hasher: H) -> Result<Self, DecodeError> {
+        let mut decoder = Decoder::new(reader, Kind::HyperLogLog)?;
+        let precision = decoder.get_usize("precision")?;
+        let mut hll = Self::try_new(precision, build_hasher)?;
+        decoder.expect_hasher(&hll.build_hasher)?;
+        let payload = decoder.finish(Some(hll.registers.as_bytes().len()))?;
+        hll.registers = BitVec::from_bytes(1 << precision, &payload);
+        // A register can exceed the number of hash bits left after the index only if the
+        // payload was not written by `to_bytes`.
+        let max_register = (65 - precision) as u8;
+        if hll.registers.iter().any(|register| register > max_register) {
+            return Err(DecodeError::InvalidPayload("register out of range"));
         }
+        Ok(hll)
+    }
+}
+
+impl<T, H> HyperLogLog<T, H>
+where
+    H: BuildHasher,
+{
+    // Unlike inclusion-exclusion over the two counts and the count of the union, which
+    // suffers from the errors of all three, this uses every pair of registers jointly.
+    // Sketches of different precisions are compared at the lower one, as in `merge`.
+    pub fn intersection(&self, other: &Self) -> Result<IntersectionEstimate, IncompatibleSketch> {
+        ensure_same_hasher(&self.build_hasher, &other.build_hasher)?;
+        let precision = self.precision.min(other.precision);
+        let (registers, other_registers) =
+            (self.registers_at(precision), other.registers_at(precision));
+
+        let mut union = registers.as_ref().clone();
+        for (index, register) in other_registers.iter().enumerate() {
+            if union.get(index) < register {
+                union.set(index, register);
+            }
+        }
+        let (a, b) = (
+            classic_estimate(&registers),
+            classic_estimate(&other_registers),
+        );
+        let intersection = (a + b - classic_estimate(&union)).max(1.);
+        let initial = [
+            (a - intersection).max(1.),
+            (b - intersection).max(1.),
+            intersection,
+        ];
+
+        let statistics = JointStatistics::new(
+            registers.iter().zip(other_registers.iter()),
+            registers.size(),
+            (65 - precision) as u8,
+        );
+        let joint = statistics.estimate(initial);
+        Ok(IntersectionEstimate {
+            intersection: joint.rates[2],
+            std_error: joint.std_errors[2],
+            only_self: joint.rates[0],
+            only_other: joint.rates[1],
+        })
     }
 }
 
@@ -59,27 +256,23 @@ where
     H: BuildHasher,
 {
     fn count(&self) -> f64 {
-        let (v, z) = self.registers.iter().fold((0, 0.), |(v, z), register| {
-            (
-                v + if register == 0 { 1 } else { 0 },
-                z + 1. / (1 << register) as f64,
-            )
-        });
-        let m = self.registers.size() as f64;
-        let estimate = self.alpha() * m * m * z;
-        let two_pow_32 = (1u64 << 32) as f64;
-
-        if estimate <= 2.5 * m && v > 0 {
-            m * (m / v as f64).ln()
-        } else if estimate > two_pow_32 / 30f64 {
-            -two_pow_32 * (1. - (estimate / two_pow_32)).ln()
-        } else {
-            estimate
-        }
+        self.count_with(Estimator::Classic)
     }
 
     fn insert(&mut self, item: &T) {
-        let hash = self.build_hasher.hash_one(item);
+        self.insert_hash(self.build_hasher.hash_one(item));
+    }
+}
+
+impl<T, H> HyperLogLog<T, H> {
+    pub fn count_with(&self, estimator: Estimator) -> f64 {
+        match estimator {
+            Estimator::Classic => classic_estimate(&self.registers),
+            Estimator::Improved => improved_estimate(&self.registers, 64 - self.precision),
+        }
+    }
+
+    fn insert_hash(&mut self, hash: u64) {
         let index = (hash >> (64 - self.precision)) as usize;
         let zeros = ((hash << self.precision) | (1 << (self.precision - 1))).leading_zeros();
         let rho = (zeros as u8) + 1;
@@ -90,8 +283,403 @@ where
     }
 }
 
+fn classic_estimate(registers: &BitVec<u8, 6>) -> f64 {
+    let (v, z) = registers.iter().fold((0, 0.), |(v, z), register| {
+        (
+            v + if register == 0 { 1 } else { 0 },
+            z + 1. / (1u64 << register) as f64,
+        )
+    });
+    let m = registers.size() as f64;
+    let estimate = alpha(registers.size()) * m * m / z;
+    let two_pow_32 = (1u64 << 32) as f64;
+
+    if estimate <= 2.5 * m && v > 0 {
+        m * (m / v as f64).ln()
+    } else if estimate > two_pow_32 / 30f64 {
+        -two_pow_32 * (1. - (estimate / two_pow_32)).ln()
+    } else {
+        estimate
+    }
+}
+
+// `q` is the number of hash bits left for rho, so registers range over `0..=q + 1`.
+fn improved_estimate(registers: &BitVec<u8, 6>, q: usize) -> f64 {
+    let mut histogram = vec![0usize; q + 2];
+    for register in registers.iter() {
+        histogram[register as usize] += 1;
+    }
+    let m = registers.size() as f64;
+
+    let mut z = m * tau(1. - histogram[q + 1] as f64 / m);
+    for &count in histogram[1..=q].iter().rev() {
+        z = 0.5 * (z + count as f64);
+    }
+    z += m * sigma(histogram[0] as f64 / m);
+    m * m / (2. * std::f64::consts::LN_2 * z)
+}
+
+// `sigma(x) = x + sum_{k >= 1} x^(2^k) 2^(k - 1)`, accounting for empty registers.
+fn sigma(mut x: f64) -> f64 {
+    if x == 1. {
+        return f64::INFINITY;
+    }
+    let mut y = 1.;
+    let mut z = x;
+    loop {
+        x *= x;
+        let previous = z;
+        z += x * y;
+        y += y;
+        if z == previous {
+            return z;
+        }
+    }
+}
+
+// `tau(x) = (1 - x - sum_{k >= 1} (1 - x^(2^-k))^2 2^-k) / 3`, accounting for saturated
+// registers.
+fn tau(mut x: f64) -> f64 {
+    if x == 0. || x == 1. {
+        return 0.;
+    }
+    let mut y = 1.;
+    let mut z = 1. - x;
+    loop {
+        x = x.sqrt();
+        let previous = z;
+        y *= 0.5;
+        z -= (1. - x).powi(2) * y;
+        if z == previous {
+            return z / 3.;
+        }
+    }
+}
+
+impl<T, H> Merge for HyperLogLog<T, H>
+where
+    H: BuildHasher,
+{
+    // Sketches of different precisions are merged at the lower one.
+    fn merge(&mut self, other: &Self) -> Result<(), IncompatibleSketch> {
+        ensure_same_hasher(&self.build_hasher, &other.build_hasher)?;
+        if self.precision > other.precision {
+            self.registers = self.folded_registers(other.precision);
+            self.precision = other.precision;
+        }
+        for (index, register) in other.registers_at(self.precision).iter().enumerate() {
+            if self.registers.get(index) < register {
+                self.registers.set(index, register);
+            }
+        }
+        Ok(())
+    }
+}
+
 impl<T, H> Debug for HyperLogLog<T, H> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "HyperLogLog {{ precision: {} }}", self.precision)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use std::hash::{BuildHasherDefault, DefaultHasher};
+
+    type DefaultHyperLogLog = HyperLogLog<u64, BuildHasherDefault<DefaultHasher>>;
+
+    fn make_hll(precision: usize, items: std::ops::Range<u64>) -> DefaultHyperLogLog {
+        let mut hll = HyperLogLog::new(precision, BuildHasherDefault::default());
+        for i in items {
+            hll.insert(&i);
+        }
+        hll
+    }
+
+    // Registers of a sketch after `n` distinct items. Beyond a few items per register, each
+    // register's count is drawn from a normal approximation of its binomial distribution and
+    // its maximum rho from `P(max <= k) = (1 - 2^-k)^count`.
+    fn simulate(precision: usize, n: u64, rng: &mut StdRng) -> DefaultHyperLogLog {
+        let mut hll = HyperLogLog::new(precision, BuildHasherDefault::default());
+        let m = 1u64 << precision;
+        if n <= 20 * m {
+            for _ in 0..n {
+                hll.insert_hash(rng.gen());
+            }
+            return hll;
+        }
+
+        let q = 64 - precision;
+        let mean = n as f64 / m as f64;
+        let std_dev = (mean * (1. - 1. / m as f64)).sqrt();
+        for index in 0..m as usize {
+            let normal = (-2. * rng.gen::<f64>().ln()).sqrt()
+                * (2. * std::f64::consts::PI * rng.gen::<f64>()).cos();
+            let count = (mean + std_dev * normal).round().max(1.);
+            let u = rng.gen::<f64>();
+            let rho = (1..=q)
+                .find(|&k| (count * (-(-(k as f64)).exp2()).ln_1p()).exp() >= u)
+                .unwrap_or(q + 1);
+            hll.registers.set(index, rho as u8);
+        }
+        hll
+    }
+
+    // Relative bias and relative root mean square error of both estimators at each
+    // cardinality.
+    fn accuracy(precision: usize, cardinalities: &[u64], trials: usize) -> Vec<[f64; 4]> {
+        let mut rng = StdRng::seed_from_u64(42);
+        cardinalities
+            .iter()
+            .map(|&n| {
+                let mut sums = [0.; 4];
+                for _ in 0..trials {
+                    let hll = simulate(precision, n, &mut rng);
+                    for (i, estimator) in [Estimator::Classic, Estimator::Improved]
+                        .into_iter()
+                        .enumerate()
+                    {
+                        let error = hll.count_with(estimator) / n as f64 - 1.;
+                        sums[2 * i] += error;
+                        sums[2 * i + 1] += error * error;
+                    }
+                }
+                let [classic_bias, classic_mse, improved_bias, improved_mse] =
+                    sums.map(|sum| sum / trials as f64);
+                [
+                    classic_bias,
+                    classic_mse.sqrt(),
+                    improved_bias,
+                    improved_mse.sqrt(),
+                ]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_try_new() {
+        let result = DefaultHyperLogLog::try_new(19, BuildHasherDefault::default());
+
+        assert_eq!(
+            result.err(),
+            Some(ParameterError::OutOfRange {
+                name: "precision",
+                range: "[4, 18]"
+            })
+        );
+    }
+
+    #[test]
+    fn test_try_with_error() {
+        let out_of_range = DefaultHyperLogLog::try_with_error(0., BuildHasherDefault::default());
+        let too_precise = DefaultHyperLogLog::try_with_error(1e-10, BuildHasherDefault::default());
+
+        assert_eq!(
+            out_of_range.err(),
+            Some(ParameterError::OutOfRange {
+                name: "epsilon",
+                range: "(0, 1)"
+            })
+        );
+        assert_eq!(
+            too_precise.err(),
+            Some(ParameterError::OutOfRange {
+                name: "precision",
+                range: "[4, 18]"
+            })
+        );
+        assert_eq!(
+            DefaultHyperLogLog::with_error(0.01, BuildHasherDefault::default()).precision(),
+            14
+        );
+    }
+
+    #[test]
+    fn test_improved_estimator_accuracy() {
+        let cardinalities = (0..9)
+            .flat_map(|e| [1, 2, 5].map(|c| c * 10u64.pow(e)))
+            .chain([1000000000])
+            .collect::<Vec<_>>();
+        let rows = accuracy(10, &cardinalities, 100);
+        let std_error = 1.04 / 32.;
+
+        for (&n, &[_, _, bias, rmse]) in cardinalities.iter().zip(&rows) {
+            assert!(bias.abs() < 0.015, "n {}: bias {}", n, bias);
+            assert!(rmse < 1.25 * std_error, "n {}: rmse {}", n, rmse);
+        }
+        let max_bias = |column: usize| rows.iter().map(|row| row[column].abs()).fold(0., f64::max);
+        assert!(max_bias(2) < max_bias(0));
+    }
+
+    #[test]
+    fn test_improved_estimator_extremes() {
+        let empty = make_hll(10, 0..0);
+        let mut saturated = make_hll(10, 0..0);
+        for index in 0..1024 {
+            saturated.registers.set(index, 55);
+        }
+
+        assert_eq!(empty.count_with(Estimator::Improved), 0.);
+        assert_eq!(saturated.count_with(Estimator::Improved), f64::INFINITY);
+        saturated.registers.set(0, 54);
+        assert!(saturated.count_with(Estimator::Improved) > 1e18);
+    }
+
+    #[test]
+    fn test_classic_estimator_accuracy() {
+        let hll = make_hll(14, 0..100000);
+        let std_error = 1.04 / 128.;
+
+        let error = hll.count() / 100000. - 1.;
+
+        assert!(error.abs() < 3. * std_error, "error {}", error);
+    }
+
+    #[test]
+    fn test_reduce_precision() {
+        let hll = make_hll(14, 0..100000);
+
+        for precision in [4, 9, 13, 14] {
+            let reduced = hll.reduce_precision(precision);
+            let direct = make_hll(precision, 0..100000);
+
+            assert_eq!(reduced.precision(), precision);
+            assert_eq!(reduced.registers.as_bytes(), direct.registers.as_bytes());
+        }
+    }
+
+    #[test]
+    fn test_reduce_precision_saturated_registers() {
+        let mut hll = make_hll(12, 0..0);
+        hll.registers.set(0, 53);
+        hll.registers.set(1, 53);
+
+        let reduced = hll.reduce_precision(10);
+
+        assert_eq!(reduced.registers.get(0), 55);
+    }
+
+    #[test]
+    #[should_panic(expected = "precision must be in the range [4, current precision]")]
+    fn test_reduce_precision_too_large() {
+        make_hll(10, 0..10).reduce_precision(12);
+    }
+
+    #[test]
+    fn test_merge_different_precisions() {
+        let mut a = make_hll(12, 0..10000);
+        let b = make_hll(10, 5000..15000);
+        let mut c = make_hll(10, 5000..15000);
+        let union = make_hll(10, 0..15000);
+
+        a.merge(&b).unwrap();
+        c.merge(&make_hll(12, 0..10000)).unwrap();
+
+        assert_eq!(a.precision(), 10);
+        assert_eq!(a.registers.as_bytes(), union.registers.as_bytes());
+        assert_eq!(c.registers.as_bytes(), union.registers.as_bytes());
+    }
+
+    #[test]
+    fn test_decode_register_out_of_range() {
+        let mut hll = make_hll(10, 0..1000);
+        hll.registers.set(3, 55);
+        assert!(
+            DefaultHyperLogLog::from_bytes(&hll.to_bytes(), BuildHasherDefault::default()).is_ok()
+        );
+
+        hll.registers.set(3, 56);
+        let decoded =
+            DefaultHyperLogLog::from_bytes(&hll.to_bytes(), BuildHasherDefault::default());
+
+        assert!(matches!(
+            decoded,
+            Err(DecodeError::InvalidPayload("register out of range"))
+        ));
+    }
+
+    #[test]
+    fn test_intersection() {
+        for (a_items, b_items, exact) in [
+            (0..100000, 50000..150000, 50000.),
+            (0..200000, 190000..210000, 10000.),
+            (0..10000, 0..10000, 10000.),
+        ] {
+            let a = make_hll(12, a_items.clone());
+            let b = make_hll(12, b_items.clone());
+
+            let estimate = a.intersection(&b).unwrap();
+
+            let error = (estimate.intersection - exact).abs();
+            assert!(
+                error < 4. * estimate.std_error,
+                "{:?}, exact {}",
+                estimate,
+                exact
+            );
+            assert!(estimate.std_error < 0.2 * exact, "{:?}", estimate);
+            let union = (a_items.end.max(b_items.end) - a_items.start.min(b_items.start)) as f64;
+            assert!((estimate.jaccard() - exact / union).abs() < 0.05);
+        }
+    }
+
+    #[test]
+    fn test_intersection_disjoint() {
+        let a = make_hll(12, 0..50000);
+        let b = make_hll(12, 50000..100000);
+
+        let estimate = a.intersection(&b).unwrap();
+
+        assert!(estimate.intersection < 2000., "{:?}", estimate);
+        assert!((estimate.union() - 100000.).abs() < 5000.);
+    }
+
+    #[test]
+    fn test_intersection_beats_inclusion_exclusion() {
+        let mut joint_error = 0.;
+        let mut inclusion_exclusion_error = 0.;
+        for run in 0..10u64 {
+            let offset = run * 1000000;
+            let a = make_hll(10, offset..offset + 100000);
+            let b = make_hll(10, offset + 90000..offset + 190000);
+            let mut union = make_hll(10, offset..offset + 100000);
+            union.merge(&b).unwrap();
+
+            let joint = a.intersection(&b).unwrap().intersection;
+            let inclusion_exclusion = a.count() + b.count() - union.count();
+            joint_error += (joint - 10000.).powi(2);
+            inclusion_exclusion_error += (inclusion_exclusion - 10000.).powi(2);
+        }
+
+        assert!(joint_error < inclusion_exclusion_error);
+    }
+
+    #[test]
+    fn test_intersection_different_precisions() {
+        let a = make_hll(12, 0..10000);
+        let b = make_hll(10, 5000..15000);
+        let reduced = make_hll(10, 0..10000);
+        let close = |x: IntersectionEstimate, y: IntersectionEstimate| {
+            [
+                (x.intersection, y.intersection),
+                (x.std_error, y.std_error),
+                (x.only_self, y.only_self),
+                (x.only_other, y.only_other),
+            ]
+            .iter()
+            .all(|(x, y)| (x - y).abs() < 1e-6 * y.abs())
+        };
+
+        assert!(close(
+            a.intersection(&b).unwrap(),
+            reduced.intersection(&b).unwrap()
+        ));
+        assert!(close(
+            b.intersection(&a).unwrap(),
+            b.intersection(&reduced).unwrap()
+        ));
+    }
+}
@@ -0,0 +1,196 @@
+// Joint maximum-likelihood estimation for a pair of HyperLogLog sketches, following Ertl,
+// "New cardinality estimation algorithms for HyperLogLog sketches", section 5.
+//
+// Under the Poisson model, the registers of `A` and `B` are the maxima of three independent
+// register values, fed by the items only in `A`, only in `B` and in both, with rates
+// `lambda_a`, `lambda_b` and `lambda_x`. The likelihood of the observed register pairs is
+// maximized over the three rates in log space.
+
+use std::collections::HashMap;
+
+const MIN_PROBABILITY: f64 = 1e-300;
+const MAX_ITERATIONS: usize = 2000;
+const TOLERANCE: f64 = 1e-10;
+const HESSIAN_STEP: f64 = 1e-2;
+
+pub(crate) struct JointStatistics {
+    // Distinct register pairs with the number of registers at which they occur.
+    pairs: Vec<(i32, i32, f64)>,
+    m: f64,
+    // Largest possible register value, so `P(K <= max_register) = 1`.
+    max_register: i32,
+}
+
+pub(crate) struct JointEstimate {
+    pub(crate) rates: [f64; 3],
+    pub(crate) std_errors: [f64; 3],
+}
+
+impl JointStatistics {
+    pub(crate) fn new(pairs: impl Iterator<Item = (u8, u8)>, m: usize, max_register: u8) -> Self {
+        let mut counts = HashMap::<(u8, u8), usize>::new();
+        for pair in pairs {
+            *counts.entry(pair).or_default() += 1;
+        }
+        Self {
+            pairs: counts
+                .into_iter()
+                .map(|((a, b), count)| (a as i32, b as i32, count as f64))
+                .collect(),
+            m: m as f64,
+            max_register: max_register as i32,
+        }
+    }
+
+    pub(crate) fn estimate(&self, initial: [f64; 3]) -> JointEstimate {
+        let objective = |x: &[f64; 3]| -self.log_likelihood(x.map(f64::exp));
+        let optimum = nelder_mead(&objective, initial.map(|rate| rate.max(1.).ln()));
+        let rates = optimum.map(f64::exp);
+
+        // Delta method on the inverse observed information in log space.
+        let std_errors = match invert(hessian(&objective, &optimum)) {
+            Some(covariance) => [0, 1, 2].map(|i| rates[i] * covariance[i][i].max(0.).sqrt()),
+            None => [f64::INFINITY; 3],
+        };
+        JointEstimate { rates, std_errors }
+    }
+
+    // `P(K <= k)` for a register fed at rate `lambda`.
+    fn cdf(&self, lambda: f64, k: i32) -> f64 {
+        if k < 0 {
+            0.
+        } else if k >= self.max_register {
+            1.
+        } else {
+            (-lambda / self.m * (-k as f64).exp2()).exp()
+        }
+    }
+
+    fn log_likelihood(&self, [lambda_a, lambda_b, lambda_x]: [f64; 3]) -> f64 {
+        let joint_cdf = |a: i32, b: i32| {
+            self.cdf(lambda_a, a) * self.cdf(lambda_b, b) * self.cdf(lambda_x, a.min(b))
+        };
+        self.pairs
+            .iter()
+            .map(|&(a, b, count)| {
+                let probability = joint_cdf(a, b) - joint_cdf(a - 1, b) - joint_cdf(a, b - 1)
+                    + joint_cdf(a - 1, b - 1);
+                count * probability.max(MIN_PROBABILITY).ln()
+            })
+            .sum()
+    }
+}
+
+fn nelder_mead(f: &impl Fn(&[f64; 3]) -> f64, start: [f64; 3]) -> [f64; 3] {
+    let mut simplex = [start; 4];
+    for (i, vertex) in simplex.iter_mut().skip(1).enumerate() {
+        vertex[i] += 1.;
+    }
+    let mut values = simplex.map(|x| f(&x));
+
+    for _ in 0..MAX_ITERATIONS {
+        let mut order = [0, 1, 2, 3];
+        order.sort_by(|&i, &j| values[i].total_cmp(&values[j]));
+        simplex = order.map(|i| simplex[i]);
+        values = order.map(|i| values[i]);
+        if (values[3] - values[0]).abs() <= TOLERANCE * (1. + values[0].abs()) {
+            break;
+        }
+
+        let centroid = [0, 1, 2].map(|d| simplex[..3].iter().map(|x| x[d]).sum::<f64>() / 3.);
+        let towards = |t: f64| [0, 1, 2].map(|d| centroid[d] + t * (simplex[3][d] - centroid[d]));
+
+        let reflected = towards(-1.);
+        let reflected_value = f(&reflected);
+        if reflected_value < values[0] {
+            let expanded = towards(-2.);
+            let expanded_value = f(&expanded);
+            (simplex[3], values[3]) = if expanded_value < reflected_value {
+                (expanded, expanded_value)
+            } else {
+                (reflected, reflected_value)
+            };
+        } else if reflected_value < values[2] {
+            (simplex[3], values[3]) = (reflected, reflected_value);
+        } else {
+            let contracted = towards(if reflected_value < values[3] {
+                -0.5
+            } else {
+                0.5
+            });
+            let contracted_value = f(&contracted);
+            if contracted_value < values[3].min(reflected_value) {
+                (simplex[3], values[3]) = (contracted, contracted_value);
+            } else {
+                for i in 1..4 {
+                    simplex[i] = [0, 1, 2].map(|d| (simplex[0][d] + simplex[i][d]) / 2.);
+                    values[i] = f(&simplex[i]);
+                }
+            }
+        }
+    }
+
+    let best = (0..4)
+        .min_by(|&i, &j| values[i].total_cmp(&values[j]))
+        .unwrap();
+    simplex[best]
+}
+
+fn hessian(f: &impl Fn(&[f64; 3]) -> f64, x: &[f64; 3]) -> [[f64; 3]; 3] {
+    let at = |di: f64, i: usize, dj: f64, j: usize| {
+        let mut y = *x;
+        y[i] += di * HESSIAN_STEP;
+        y[j] += dj * HESSIAN_STEP;
+        f(&y)
+    };
+    [0, 1, 2].map(|i| {
+        [0, 1, 2].map(|j| {
+            (at(1., i, 1., j) - at(1., i, -1., j) - at(-1., i, 1., j) + at(-1., i, -1., j))
+                / (4. * HESSIAN_STEP * HESSIAN_STEP)
+        })
+    })
+}
+
+fn invert(m: [[f64; 3]; 3]) -> Option<[[f64; 3]; 3]> {
+    let cofactor = |i: usize, j: usize| {
+        let (r0, r1) = ((i + 1) % 3, (i + 2) % 3);
+        let (c0, c1) = ((j + 1) % 3, (j + 2) % 3);
+        m[r0][c0] * m[r1][c1] - m[r0][c1] * m[r1][c0]
+    };
+    let determinant = (0..3).map(|j| m[0][j] * cofactor(0, j)).sum::<f64>();
+    if !determinant.is_normal() {
+        return None;
+    }
+    Some([0, 1, 2].map(|i| [0, 1, 2].map(|j| cofactor(j, i) / determinant)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invert() {
+        let m = [[4., 1., 2.], [1., 3., 0.], [2., 0., 5.]];
+        let inverse = invert(m).unwrap();
+
+        let product = [0, 1, 2]
+            .map(|i| [0, 1, 2].map(|j| (0..3).map(|k| m[i][k] * inverse[k][j]).sum::<f64>()));
+        for (i, row) in product.iter().enumerate() {
+            for (j, &value) in row.iter().enumerate() {
+                assert!((value - if i == j { 1. } else { 0. }).abs() < 1e-12);
+            }
+        }
+        assert!(invert([[1., 2., 3.], [2., 4., 6.], [0., 0., 1.]]).is_none());
+    }
+
+    #[test]
+    fn test_nelder_mead() {
+        let f =
+            |x: &[f64; 3]| (x[0] - 1.).powi(2) + 2. * (x[1] + 2.).powi(2) + (x[2] - 0.5).powi(4);
+        let optimum = nelder_mead(&f, [0., 0., 0.]);
+
+        assert!((optimum[0] - 1.).abs() < 1e-3);
+        assert!((optimum[1] + 2.).abs() < 1e-3);
+        assert!((optimum[2] - 0.5).abs() < 0.05);
+    }
+}
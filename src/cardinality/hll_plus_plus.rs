@@ -0,0 +1,361 @@
+use crate::bit_vec::BitVec;
+use crate::cardinality::hll::alpha;
+use crate::cardinality::hll_bias::{BIASES, RAW_ESTIMATES};
+use crate::cardinality::Cardinality;
+use crate::error::{ensure, ParameterError};
+use std::fmt::{Debug, Formatter};
+use std::hash::{BuildHasher, Hash};
+use std::marker::PhantomData;
+
+const SPARSE_PRECISION: usize = 25;
+const BIAS_NEIGHBOURS: usize = 6;
+const THRESHOLDS: [f64; 15] = [
+    10., 20., 40., 80., 220., 400., 900., 1800., 3100., 6500., 11500., 20000., 50000., 120000.,
+    350000.,
+];
+
+enum Registers {
+    // `entries` is sorted by sparse index with one entry per index, see `encode` for the layout
+    // of entries. As in Heule et al., new entries go to `buffer` and are merged in once it is
+    // full, so an insert does not shift the sorted list.
+    Sparse { entries: Vec<u32>, buffer: Vec<u32> },
+    Dense(BitVec<u8, 6>),
+}
+
+pub struct HyperLogLogPlusPlus<T, H> {
+    registers: Registers,
+    precision: usize,
+    build_hasher: H,
+    _phantom: PhantomData<T>,
+}
+
+impl<T, H> HyperLogLogPlusPlus<T, H> {
+    pub fn new(precision: usize, build_hasher: H) -> Self {
+        Self::try_new(precision, build_hasher).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(precision: usize, build_hasher: H) -> Result<Self, ParameterError> {
+        ensure(
+            (4..=18).contains(&precision),
+            ParameterError::OutOfRange {
+                name: "precision",
+                range: "[4, 18]",
+            },
+        )?;
+        Ok(Self {
+            registers: Registers::Sparse {
+                entries: Vec::new(),
+                buffer: Vec::new(),
+            },
+            precision,
+            build_hasher,
+            _phantom: PhantomData,
+        })
+    }
+
+    pub fn with_error(epsilon: f64, build_hasher: H) -> Self {
+        Self::try_with_error(epsilon, build_hasher).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_with_error(epsilon: f64, build_hasher: H) -> Result<Self, ParameterError> {
+        ensure(
+            0.0 < epsilon && epsilon < 1.0,
+            ParameterError::OutOfRange {
+                name: "epsilon",
+                range: "(0, 1)",
+            },
+        )?;
+        let m = (1.04 / epsilon).powi(2);
+        let precision = m.log2().ceil() as usize;
+        Self::try_new(precision, build_hasher)
+    }
+
+    pub fn precision(&self) -> usize {
+        self.precision
+    }
+
+    pub fn is_sparse(&self) -> bool {
+        matches!(self.registers, Registers::Sparse { .. })
+    }
+
+    // Switch once the sparse list takes more memory than the 6-bit dense registers.
+    fn sparse_limit(&self) -> usize {
+        (6 << self.precision) / 32
+    }
+
+    fn buffer_limit(&self) -> usize {
+        self.sparse_limit() / 4
+    }
+
+    // Entries keep the top 25 bits of the hash as the sparse index. If the bits between
+    // the dense and sparse precision are all zero, rho of the remaining bits is needed
+    // to recover the dense register and is stored alongside a flag bit.
+    fn encode(&self, hash: u64) -> u32 {
+        let index = (hash >> (64 - SPARSE_PRECISION)) as u32;
+        if index & ((1 << (SPARSE_PRECISION - self.precision)) - 1) == 0 {
+            let zeros =
+                ((hash << SPARSE_PRECISION) | (1 << (SPARSE_PRECISION - 1))).leading_zeros();
+            (index << 7) | ((zeros + 1) << 1) | 1
+        } else {
+            index << 1
+        }
+    }
+
+    fn decode(&self, entry: u32) -> (usize, u8) {
+        let shift = SPARSE_PRECISION - self.precision;
+        let index = sparse_index(entry);
+        let rho = if entry & 1 == 1 {
+            ((entry >> 1) & 0x3f) as u8 + shift as u8
+        } else {
+            (index << (32 - shift)).leading_zeros() as u8 + 1
+        };
+        ((index >> shift) as usize, rho)
+    }
+
+    fn convert_to_dense(&mut self) {
+        let mut registers = BitVec::new(1 << self.precision);
+        if let Registers::Sparse { entries, buffer } = &self.registers {
+            for &entry in entries.iter().chain(buffer) {
+                let (index, rho) = self.decode(entry);
+                if registers.get(index) < rho {
+                    registers.set(index, rho);
+                }
+            }
+        }
+        self.registers = Registers::Dense(registers);
+    }
+
+    fn estimate_bias(&self, estimate: f64) -> f64 {
+        let raw_estimates = RAW_ESTIMATES[self.precision - 4];
+        let biases = BIASES[self.precision - 4];
+        let mut neighbours = (0..raw_estimates.len()).collect::<Vec<_>>();
+        neighbours.sort_by(|&a, &b| {
+            let a = (raw_estimates[a] - estimate).abs();
+            let b = (raw_estimates[b] - estimate).abs();
+            a.total_cmp(&b)
+        });
+        neighbours
+            .iter()
+            .take(BIAS_NEIGHBOURS)
+            .map(|&i| biases[i])
+            .sum::<f64>()
+            / BIAS_NEIGHBOURS as f64
+    }
+}
+
+impl<T, H> Cardinality<T> for HyperLogLogPlusPlus<T, H>
+where
+    T: Hash,
+    H: BuildHasher,
+{
+    fn count(&self) -> f64 {
+        let registers = match &self.registers {
+            Registers::Sparse { entries, buffer } => {
+                let mut entries = entries.clone();
+                merge_sparse(&mut entries, buffer);
+                let m = (1 << SPARSE_PRECISION) as f64;
+                let v = m - entries.len() as f64;
+                return m * (m / v).ln();
+            }
+            Registers::Dense(registers) => registers,
+        };
+
+        let (v, z) = registers.iter().fold((0, 0.), |(v, z), register| {
+            (
+                v + if register == 0 { 1 } else { 0 },
+                z + 1. / (1u64 << register) as f64,
+            )
+        });
+        let m = registers.size() as f64;
+        let estimate = alpha(registers.size()) * m * m / z;
+        let estimate = if estimate <= 5. * m {
+            estimate - self.estimate_bias(estimate)
+        } else {
+            estimate
+        };
+
+        if v > 0 {
+            let linear_count = m * (m / v as f64).ln();
+            if linear_count <= THRESHOLDS[self.precision - 4] {
+                return linear_count;
+            }
+        }
+        estimate
+    }
+
+    fn insert(&mut self, item: &T) {
+        let hash = self.build_hasher.hash_one(item);
+        let entry = self.encode(hash);
+        let sparse_limit = self.sparse_limit();
+        let buffer_limit = self.buffer_limit();
+
+        match &mut self.registers {
+            Registers::Sparse { entries, buffer } => {
+                buffer.push(entry);
+                if buffer.len() > buffer_limit {
+                    merge_sparse(entries, buffer);
+                    buffer.clear();
+                    if entries.len() > sparse_limit {
+                        self.convert_to_dense();
+                    }
+                }
+            }
+            Registers::Dense(registers) => {
+                let index = (hash >> (64 - self.precision)) as usize;
+                let zeros =
+                    ((hash << self.precision) | (1 << (self.precision - 1))).leading_zeros();
+                let rho = (zeros as u8) + 1;
+                if registers.get(index) < rho {
+                    registers.set(index, rho);
+                }
+            }
+        }
+    }
+}
+
+impl<T, H> Debug for HyperLogLogPlusPlus<T, H> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "HyperLogLogPlusPlus {{ precision: {}, sparse: {} }}",
+            self.precision,
+            self.is_sparse()
+        )
+    }
+}
+
+fn sparse_index(entry: u32) -> u32 {
+    if entry & 1 == 1 {
+        entry >> 7
+    } else {
+        entry >> 1
+    }
+}
+
+// Keeps the largest entry, which has the largest rho, for each sparse index. The stable sort
+// only has to merge two sorted runs once the buffer is sorted.
+fn merge_sparse(entries: &mut Vec<u32>, buffer: &[u32]) {
+    let start = entries.len();
+    entries.extend_from_slice(buffer);
+    entries[start..].sort_unstable_by_key(|&entry| sparse_index(entry));
+    entries.sort_by_key(|&entry| sparse_index(entry));
+    entries.dedup_by(|entry, kept| {
+        if sparse_index(*entry) == sparse_index(*kept) {
+            *kept = (*kept).max(*entry);
+            true
+        } else {
+            false
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::hash::{BuildHasherDefault, DefaultHasher};
+
+    fn make_hll(precision: usize) -> HyperLogLogPlusPlus<u64, BuildHasherDefault<DefaultHasher>> {
+        HyperLogLogPlusPlus::new(precision, BuildHasherDefault::default())
+    }
+
+    fn dense_register(precision: usize, hash: u64) -> (usize, u8) {
+        let index = (hash >> (64 - precision)) as usize;
+        let zeros = ((hash << precision) | (1 << (precision - 1))).leading_zeros();
+        (index, zeros as u8 + 1)
+    }
+
+    #[test]
+    #[should_panic(expected = "precision must be in the range [4, 18]")]
+    fn test_precision_too_large() {
+        make_hll(19);
+    }
+
+    #[test]
+    fn test_encode_decode() {
+        let hll = make_hll(14);
+        let hashes = [
+            0u64,
+            u64::MAX,
+            0xffff_c000_0000_0000,
+            0xffff_c000_0000_0001,
+            0x0000_2000_0000_0000,
+            0x1234_5678_9abc_def0,
+            0x0000_0000_0000_8000,
+        ];
+
+        for hash in hashes {
+            assert_eq!(hll.decode(hll.encode(hash)), dense_register(14, hash));
+        }
+    }
+
+    #[test]
+    fn test_sparse_small_counts() {
+        let mut hll = make_hll(14);
+
+        for i in 0..1000 {
+            hll.insert(&i);
+        }
+
+        assert!(hll.is_sparse());
+        assert!((hll.count() - 1000.).abs() < 5.);
+    }
+
+    #[test]
+    fn test_sparse_duplicates() {
+        let mut hll = make_hll(10);
+
+        for _ in 0..10 {
+            for i in 0..100 {
+                hll.insert(&i);
+            }
+        }
+
+        assert!(hll.is_sparse());
+        assert!((hll.count() - 100.).abs() < 1.);
+    }
+
+    #[test]
+    fn test_to_dense_matches_dense_insert() {
+        let mut hll = make_hll(8);
+        let mut expected = BitVec::<u8, 6>::new(1 << 8);
+
+        for i in 0..10000u64 {
+            hll.insert(&i);
+            let (index, rho) = dense_register(8, hll.build_hasher.hash_one(i));
+            if expected.get(index) < rho {
+                expected.set(index, rho);
+            }
+        }
+
+        match &hll.registers {
+            Registers::Sparse { .. } => panic!("expected dense registers"),
+            Registers::Dense(registers) => {
+                assert!(registers.iter().eq(expected.iter()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_accuracy() {
+        for precision in [6, 10, 14] {
+            let error = 1.04 / ((1 << precision) as f64).sqrt();
+            let mut hll = make_hll(precision);
+            let mut inserted = 0;
+
+            for n in [10u64, 100, 1000, 10000, 100000] {
+                while inserted < n {
+                    hll.insert(&inserted);
+                    inserted += 1;
+                }
+                let relative_error = (hll.count() - n as f64).abs() / n as f64;
+                assert!(
+                    relative_error < 3. * error,
+                    "precision {}, n {}: relative error {}",
+                    precision,
+                    n,
+                    relative_error
+                );
+            }
+        }
+    }
+}
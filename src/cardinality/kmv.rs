@@ -0,0 +1,198 @@
+use crate::cardinality::theta::ThetaSketch;
+use crate::cardinality::Cardinality;
+use crate::error::{ensure, ParameterError};
+use crate::merge::{ensure_eq, ensure_same_hasher, IncompatibleSketch, Merge};
+use std::fmt::{Debug, Formatter};
+use std::hash::{BuildHasher, Hash};
+
+// Bar-Yossef et al., "Counting Distinct Elements in a Data Stream". Keeps the `k` smallest
+// distinct hashes, which is exactly what a theta sketch of the same `k` retains, and
+// estimates from the largest of them instead. Counts are exact until `k` distinct items
+// have been seen.
+pub struct KMinValues<T, H> {
+    sketch: ThetaSketch<T, H>,
+}
+
+impl<T, H> KMinValues<T, H> {
+    pub fn new(k: usize, build_hasher: H) -> Self {
+        Self::try_new(k, build_hasher).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(k: usize, build_hasher: H) -> Result<Self, ParameterError> {
+        // The estimate divides by the `k`-th smallest hash scaled by `k - 1`.
+        ensure(
+            k > 1,
+            ParameterError::NotGreaterThan {
+                name: "k",
+                bound: 1,
+            },
+        )?;
+        Ok(Self {
+            sketch: ThetaSketch::try_new(k, build_hasher)?,
+        })
+    }
+
+    pub fn k(&self) -> usize {
+        self.sketch.k()
+    }
+
+    // The retained hashes in ascending order, a uniform sample of the distinct items.
+    pub fn hashes(&self) -> impl Iterator<Item = u64> + '_ {
+        self.sketch.retained().iter().copied()
+    }
+}
+
+impl<T, H> KMinValues<T, H>
+where
+    H: BuildHasher,
+{
+    // Fraction of the `k` smallest hashes of the union that are present in both sketches.
+    pub fn jaccard(&self, other: &Self) -> Result<f64, IncompatibleSketch> {
+        self.ensure_compatible(other)?;
+        let (a, b) = (self.sketch.retained(), other.sketch.retained());
+        let union = a.union(b).take(self.k()).collect::<Vec<_>>();
+        if union.is_empty() {
+            return Ok(0.);
+        }
+        let shared = union
+            .iter()
+            .filter(|hash| a.contains(hash) && b.contains(hash))
+            .count();
+        Ok(shared as f64 / union.len() as f64)
+    }
+
+    fn ensure_compatible(&self, other: &Self) -> Result<(), IncompatibleSketch> {
+        ensure_eq(self.k(), other.k(), IncompatibleSketch::K)?;
+        ensure_same_hasher(self.sketch.build_hasher(), other.sketch.build_hasher())
+    }
+}
+
+impl<T, H> Cardinality<T> for KMinValues<T, H>
+where
+    T: Hash,
+    H: BuildHasher,
+{
+    fn count(&self) -> f64 {
+        let hashes = self.sketch.retained();
+        if hashes.len() < self.k() {
+            return hashes.len() as f64;
+        }
+        let largest = *hashes.last().unwrap() as f64 / u64::MAX as f64;
+        (self.k() - 1) as f64 / largest
+    }
+
+    fn insert(&mut self, item: &T) {
+        self.sketch.insert(item);
+    }
+}
+
+impl<T, H> Merge for KMinValues<T, H>
+where
+    H: BuildHasher,
+{
+    fn merge(&mut self, other: &Self) -> Result<(), IncompatibleSketch> {
+        self.ensure_compatible(other)?;
+        self.sketch.merge(&other.sketch)
+    }
+}
+
+impl<T, H> Debug for KMinValues<T, H> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "KMinValues {{ k: {}, num_retained: {} }}",
+            self.k(),
+            self.sketch.num_retained()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::hash::{BuildHasherDefault, DefaultHasher};
+
+    type DefaultKMinValues = KMinValues<u64, BuildHasherDefault<DefaultHasher>>;
+
+    fn make_kmv(k: usize, items: std::ops::Range<u64>) -> DefaultKMinValues {
+        let mut kmv = KMinValues::new(k, BuildHasherDefault::default());
+        for i in items {
+            kmv.insert(&i);
+        }
+        kmv
+    }
+
+    #[test]
+    #[should_panic(expected = "k must be > 1")]
+    fn test_k_too_small() {
+        make_kmv(0, 0..0);
+    }
+
+    #[test]
+    #[should_panic(expected = "k must be > 1")]
+    fn test_k_one() {
+        make_kmv(1, 0..0);
+    }
+
+    #[test]
+    fn test_exact_at_small_counts() {
+        let mut kmv = make_kmv(1024, 0..1000);
+        for i in 0..1000 {
+            kmv.insert(&i);
+        }
+
+        assert_eq!(kmv.count(), 1000.);
+        assert_eq!(kmv.hashes().count(), 1000);
+    }
+
+    #[test]
+    fn test_accuracy() {
+        let kmv = make_kmv(1024, 0..1000000);
+
+        assert_eq!(kmv.hashes().count(), 1024);
+        assert!((kmv.count() - 1e6).abs() / 1e6 < 3. / 1023f64.sqrt());
+    }
+
+    #[test]
+    fn test_hashes_are_smallest() {
+        let kmv = make_kmv(16, 0..1000);
+        let build_hasher = BuildHasherDefault::<DefaultHasher>::default();
+        let mut expected = (0..1000u64)
+            .map(|i| build_hasher.hash_one(i))
+            .collect::<Vec<_>>();
+        expected.sort_unstable();
+
+        assert!(kmv.hashes().eq(expected.into_iter().take(16)));
+    }
+
+    #[test]
+    fn test_merge_matches_union() {
+        let mut a = make_kmv(256, 0..6000);
+        let b = make_kmv(256, 4000..10000);
+        let union = make_kmv(256, 0..10000);
+
+        a.merge(&b).unwrap();
+
+        assert!(a.hashes().eq(union.hashes()));
+        assert_eq!(
+            a.merge(&make_kmv(128, 0..10)),
+            Err(IncompatibleSketch::K(256, 128))
+        );
+    }
+
+    #[test]
+    fn test_jaccard() {
+        let a = make_kmv(1024, 0..60000);
+        let b = make_kmv(1024, 40000..100000);
+
+        assert!((a.jaccard(&b).unwrap() - 0.2).abs() < 0.05);
+        assert_eq!(
+            make_kmv(64, 0..10).jaccard(&make_kmv(64, 5..15)),
+            Ok(1. / 3.)
+        );
+        assert_eq!(
+            a.jaccard(&make_kmv(64, 0..10)),
+            Err(IncompatibleSketch::K(1024, 64))
+        );
+    }
+}
@@ -1,7 +1,11 @@
 use crate::cardinality::Cardinality;
+use crate::encoding::{decode_exact, pack_bits, unpack_bits, DecodeError, Decoder, Encoder, Kind};
+use crate::error::{ensure_positive, ParameterError};
+use crate::merge::{ensure_eq, ensure_same_hasher, IncompatibleSketch, Merge};
 use fixedbitset::FixedBitSet;
 use std::fmt::{Debug, Formatter};
 use std::hash::{BuildHasher, Hash};
+use std::io::{Read, Write};
 use std::marker::PhantomData;
 
 pub struct LinearCount<T, H> {
@@ -13,12 +17,49 @@ pub struct LinearCount<T, H> {
 
 impl<T, H> LinearCount<T, H> {
     pub fn new(num_bits: usize, build_hasher: H) -> Self {
-        Self {
+        Self::try_new(num_bits, build_hasher).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(num_bits: usize, build_hasher: H) -> Result<Self, ParameterError> {
+        ensure_positive("num_bits", num_bits)?;
+        Ok(Self {
             bits: FixedBitSet::with_capacity(num_bits),
             zeros: num_bits,
             build_hasher,
             _phantom: PhantomData,
-        }
+        })
+    }
+}
+
+impl<T, H> LinearCount<T, H>
+where
+    H: BuildHasher,
+{
+    pub fn to_bytes(&self) -> Vec<u8> {
+        Encoder::new(Kind::LinearCount)
+            .put_u64(self.bits.len() as u64)
+            .put_hasher(&self.build_hasher)
+            .finish(&pack_bits(&self.bits))
+    }
+
+    pub fn write_to<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+        writer.write_all(&self.to_bytes())
+    }
+
+    pub fn from_bytes(bytes: &[u8], build_hasher: H) -> Result<Self, DecodeError> {
+        decode_exact(bytes, |reader| Self::read_from(reader, build_hasher))
+    }
+
+    pub fn read_from<R: Read>(reader: R, build_hasher: H) -> Result<Self, DecodeError> {
+        let mut decoder = Decoder::new(reader, Kind::LinearCount)?;
+        let num_bits = decoder.get_usize("num_bits")?;
+        decoder.expect_hasher(&build_hasher)?;
+        let payload = decoder.finish(Some(num_bits.div_ceil(8)))?;
+
+        let mut lc = Self::try_new(num_bits, build_hasher)?;
+        lc.bits = unpack_bits(num_bits, &payload);
+        lc.zeros = lc.bits.count_zeroes(..);
+        Ok(lc)
     }
 }
 
@@ -40,11 +81,28 @@ where
         let hash = self.build_hasher.hash_one(item);
         let index = hash as usize % self.bits.len();
         if !self.bits.put(index) {
-            self.zeros += 1;
+            self.zeros -= 1;
         }
     }
 }
 
+impl<T, H> Merge for LinearCount<T, H>
+where
+    H: BuildHasher,
+{
+    fn merge(&mut self, other: &Self) -> Result<(), IncompatibleSketch> {
+        ensure_eq(
+            self.bits.len(),
+            other.bits.len(),
+            IncompatibleSketch::NumBits,
+        )?;
+        ensure_same_hasher(&self.build_hasher, &other.build_hasher)?;
+        self.bits.union_with(&other.bits);
+        self.zeros = self.bits.count_zeroes(..);
+        Ok(())
+    }
+}
+
 impl<T, H> Debug for LinearCount<T, H> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "LinearCount {{ num_bits: {} }}", self.bits.len())
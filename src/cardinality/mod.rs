@@ -1,6 +1,11 @@
 pub mod hash_set;
 pub mod hll;
+mod hll_bias;
+mod hll_joint;
+pub mod hll_plus_plus;
+pub mod kmv;
 pub mod linear_count;
+pub mod theta;
 
 pub trait Cardinality<T> {
     fn count(&self) -> f64;
@@ -0,0 +1,268 @@
+use crate::cardinality::Cardinality;
+use crate::error::{ensure_positive, ParameterError};
+use crate::merge::{ensure_same_hasher, IncompatibleSketch, Merge};
+use std::collections::BTreeSet;
+use std::fmt::{Debug, Formatter};
+use std::hash::{BuildHasher, Hash};
+use std::marker::PhantomData;
+
+// Keeps every distinct hash below `theta`, lowering `theta` to the largest retained hash
+// whenever more than `k` are kept. The retained hashes are a uniform sample of the distinct
+// items at rate `theta / 2^64`, which carries over to unions, intersections and differences.
+pub struct ThetaSketch<T, H> {
+    hashes: BTreeSet<u64>,
+    theta: u64,
+    k: usize,
+    build_hasher: H,
+    _phantom: PhantomData<T>,
+}
+
+impl<T, H> ThetaSketch<T, H> {
+    pub fn new(k: usize, build_hasher: H) -> Self {
+        Self::try_new(k, build_hasher).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(k: usize, build_hasher: H) -> Result<Self, ParameterError> {
+        ensure_positive("k", k)?;
+        Ok(Self {
+            hashes: BTreeSet::new(),
+            theta: u64::MAX,
+            k,
+            build_hasher,
+            _phantom: PhantomData,
+        })
+    }
+
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    pub fn num_retained(&self) -> usize {
+        self.hashes.len()
+    }
+
+    // Below `theta`, these are the smallest distinct hashes seen, at most `k` of them.
+    pub(crate) fn retained(&self) -> &BTreeSet<u64> {
+        &self.hashes
+    }
+
+    pub(crate) fn build_hasher(&self) -> &H {
+        &self.build_hasher
+    }
+
+    pub fn is_estimation_mode(&self) -> bool {
+        self.theta < u64::MAX
+    }
+
+    pub fn theta(&self) -> f64 {
+        self.theta as f64 / u64::MAX as f64
+    }
+
+    // Normal approximation to the binomial number of retained hashes. Bounds never fall
+    // below the number of hashes actually seen.
+    pub fn lower_bound(&self, num_std_devs: f64) -> f64 {
+        let (estimate, std_dev) = self.estimate_and_std_dev();
+        (estimate - num_std_devs * std_dev).max(self.hashes.len() as f64)
+    }
+
+    pub fn upper_bound(&self, num_std_devs: f64) -> f64 {
+        let (estimate, std_dev) = self.estimate_and_std_dev();
+        estimate + num_std_devs * std_dev
+    }
+
+    fn estimate_and_std_dev(&self) -> (f64, f64) {
+        let retained = self.hashes.len() as f64;
+        if !self.is_estimation_mode() {
+            return (retained, 0.);
+        }
+        let p = self.theta();
+        (retained / p, (retained * (1. - p)).sqrt() / p)
+    }
+
+    fn shrink_to_k(&mut self) {
+        while self.hashes.len() > self.k {
+            self.theta = self.hashes.pop_last().unwrap();
+        }
+    }
+}
+
+impl<T, H> ThetaSketch<T, H>
+where
+    H: BuildHasher + Clone,
+{
+    pub fn union(&self, other: &Self) -> Result<Self, IncompatibleSketch> {
+        let theta = self.combined_theta(other)?;
+        let mut result = self.with_hashes(theta, self.hashes.union(&other.hashes));
+        result.shrink_to_k();
+        Ok(result)
+    }
+
+    pub fn intersect(&self, other: &Self) -> Result<Self, IncompatibleSketch> {
+        let theta = self.combined_theta(other)?;
+        Ok(self.with_hashes(theta, self.hashes.intersection(&other.hashes)))
+    }
+
+    // Items in `self` but not in `other`.
+    pub fn a_not_b(&self, other: &Self) -> Result<Self, IncompatibleSketch> {
+        let theta = self.combined_theta(other)?;
+        Ok(self.with_hashes(theta, self.hashes.difference(&other.hashes)))
+    }
+
+    // Both sketches are only complete below the smaller theta.
+    fn combined_theta(&self, other: &Self) -> Result<u64, IncompatibleSketch> {
+        ensure_same_hasher(&self.build_hasher, &other.build_hasher)?;
+        Ok(self.theta.min(other.theta))
+    }
+
+    fn with_hashes<'a>(&self, theta: u64, hashes: impl Iterator<Item = &'a u64>) -> Self {
+        Self {
+            hashes: hashes.copied().filter(|&hash| hash < theta).collect(),
+            theta,
+            k: self.k,
+            build_hasher: self.build_hasher.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T, H> Cardinality<T> for ThetaSketch<T, H>
+where
+    T: Hash,
+    H: BuildHasher,
+{
+    fn count(&self) -> f64 {
+        self.estimate_and_std_dev().0
+    }
+
+    fn insert(&mut self, item: &T) {
+        let hash = self.build_hasher.hash_one(item);
+        if hash < self.theta && self.hashes.insert(hash) {
+            self.shrink_to_k();
+        }
+    }
+}
+
+impl<T, H> Merge for ThetaSketch<T, H>
+where
+    H: BuildHasher,
+{
+    fn merge(&mut self, other: &Self) -> Result<(), IncompatibleSketch> {
+        ensure_same_hasher(&self.build_hasher, &other.build_hasher)?;
+        self.theta = self.theta.min(other.theta);
+        self.hashes.extend(&other.hashes);
+        let theta = self.theta;
+        self.hashes.retain(|&hash| hash < theta);
+        self.shrink_to_k();
+        Ok(())
+    }
+}
+
+impl<T, H> Debug for ThetaSketch<T, H> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "ThetaSketch {{ k: {}, num_retained: {}, theta: {} }}",
+            self.k,
+            self.hashes.len(),
+            self.theta()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::hash::{BuildHasherDefault, DefaultHasher, RandomState};
+
+    type DefaultThetaSketch = ThetaSketch<u64, BuildHasherDefault<DefaultHasher>>;
+
+    fn make_sketch(items: std::ops::Range<u64>) -> DefaultThetaSketch {
+        let mut sketch = ThetaSketch::new(4096, BuildHasherDefault::default());
+        for i in items {
+            sketch.insert(&i);
+        }
+        sketch
+    }
+
+    fn assert_within_bounds(sketch: &DefaultThetaSketch, exact: f64) {
+        assert!(
+            sketch.lower_bound(3.) <= exact && exact <= sketch.upper_bound(3.),
+            "{:?}: {} not in [{}, {}]",
+            sketch,
+            exact,
+            sketch.lower_bound(3.),
+            sketch.upper_bound(3.)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "k must be > 0")]
+    fn test_k_too_small() {
+        DefaultThetaSketch::new(0, BuildHasherDefault::default());
+    }
+
+    #[test]
+    fn test_exact_mode() {
+        let sketch = make_sketch(0..1000);
+
+        assert!(!sketch.is_estimation_mode());
+        assert_eq!(sketch.count(), 1000.);
+        assert_eq!(sketch.lower_bound(2.), 1000.);
+        assert_eq!(sketch.upper_bound(2.), 1000.);
+    }
+
+    #[test]
+    fn test_estimation_mode() {
+        let sketch = make_sketch(0..1000000);
+
+        assert!(sketch.is_estimation_mode());
+        assert_eq!(sketch.num_retained(), 4096);
+        assert!((sketch.count() - 1e6).abs() / 1e6 < 3. / 4096f64.sqrt());
+        assert_within_bounds(&sketch, 1e6);
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = make_sketch(0..600000);
+        let b = make_sketch(400000..1000000);
+
+        let union = a.union(&b).unwrap();
+        let intersection = a.intersect(&b).unwrap();
+        let difference = a.a_not_b(&b).unwrap();
+
+        assert_within_bounds(&union, 1e6);
+        assert_within_bounds(&intersection, 2e5);
+        assert_within_bounds(&difference, 4e5);
+        assert!((intersection.count() - 2e5).abs() / 2e5 < 0.1);
+        assert!((difference.count() - 4e5).abs() / 4e5 < 0.1);
+    }
+
+    #[test]
+    fn test_set_operations_exact() {
+        let a = make_sketch(0..100);
+        let b = make_sketch(50..120);
+
+        assert_eq!(a.union(&b).unwrap().count(), 120.);
+        assert_eq!(a.intersect(&b).unwrap().count(), 50.);
+        assert_eq!(a.a_not_b(&b).unwrap().count(), 50.);
+    }
+
+    #[test]
+    fn test_merge_matches_union() {
+        let mut a = make_sketch(0..600000);
+        let b = make_sketch(400000..1000000);
+        let union = a.union(&b).unwrap();
+
+        a.merge(&b).unwrap();
+
+        assert_eq!(a.count(), union.count());
+    }
+
+    #[test]
+    fn test_different_hasher() {
+        let a = ThetaSketch::<u64, _>::new(64, RandomState::new());
+        let b = ThetaSketch::new(64, RandomState::new());
+
+        assert_eq!(a.intersect(&b).err(), Some(IncompatibleSketch::Hasher));
+    }
+}
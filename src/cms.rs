@@ -1,8 +1,12 @@
+use crate::encoding::{decode_exact, DecodeError, Decoder, Encoder, Kind};
+use crate::error::{checked_usize, ensure, ensure_positive, ParameterError};
 use crate::hash::iter_hashes;
-use num_traits::{SaturatingAdd, Unsigned};
+use crate::merge::{ensure_eq, ensure_same_hasher, IncompatibleSketch, Merge};
+use num_traits::{NumCast, PrimInt, SaturatingAdd, Unsigned};
 use std::f64::consts::E;
 use std::fmt::{Debug, Formatter};
 use std::hash::{BuildHasher, Hash};
+use std::io::{Read, Write};
 use std::marker::PhantomData;
 
 #[derive(Clone)]
@@ -19,30 +23,51 @@ where
     C: Clone + Unsigned,
 {
     pub fn new(width: usize, depth: usize, build_hasher: H) -> Self {
-        assert!(width > 0, "width must be > 0");
-        assert!(depth > 0, "depth must be > 0");
-        let size = width.checked_mul(depth).expect("width * depth overflow");
-        Self {
+        Self::try_new(width, depth, build_hasher).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(width: usize, depth: usize, build_hasher: H) -> Result<Self, ParameterError> {
+        ensure_positive("width", width)?;
+        ensure_positive("depth", depth)?;
+        let size = width.checked_mul(depth).ok_or(ParameterError::Overflow {
+            name: "width * depth",
+        })?;
+        Ok(Self {
             counters: vec![C::zero(); size],
             width,
             depth,
             build_hasher,
             _phantom: PhantomData,
-        }
+        })
     }
 
     pub fn with_error_bounds(epsilon: f64, delta: f64, build_hasher: H) -> Self {
-        assert!(
+        Self::try_with_error_bounds(epsilon, delta, build_hasher)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_with_error_bounds(
+        epsilon: f64,
+        delta: f64,
+        build_hasher: H,
+    ) -> Result<Self, ParameterError> {
+        ensure(
             0. < epsilon && epsilon <= 1.,
-            "epsilon must be in the range (0, 1]"
-        );
-        assert!(
+            ParameterError::OutOfRange {
+                name: "epsilon",
+                range: "(0, 1]",
+            },
+        )?;
+        ensure(
             0. < delta && delta < 1.,
-            "delta must be in the range (0, 1)"
-        );
-        let width = (E / epsilon).ceil() as usize;
-        let depth = (1. / delta).ceil() as usize;
-        Self::new(width, depth, build_hasher)
+            ParameterError::OutOfRange {
+                name: "delta",
+                range: "(0, 1)",
+            },
+        )?;
+        let width = checked_usize("width", (E / epsilon).ceil())?;
+        let depth = checked_usize("depth", (1. / delta).ceil())?;
+        Self::try_new(width, depth, build_hasher)
     }
 
     pub fn width(&self) -> usize {
@@ -58,6 +83,65 @@ where
     }
 }
 
+impl<T, H, C> CountMinSketch<T, H, C>
+where
+    C: PrimInt + Unsigned,
+    H: BuildHasher,
+{
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let counter_size = size_of::<C>();
+        let payload = self
+            .counters
+            .iter()
+            .flat_map(|counter| {
+                let bytes = counter.to_u128().unwrap().to_le_bytes();
+                bytes.into_iter().take(counter_size)
+            })
+            .collect::<Vec<_>>();
+        Encoder::new(Kind::CountMinSketch)
+            .put_u64(counter_size as u64)
+            .put_u64(self.width as u64)
+            .put_u64(self.depth as u64)
+            .put_hasher(&self.build_hasher)
+            .finish(&payload)
+    }
+
+    pub fn write_to<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+        writer.write_all(&self.to_bytes())
+    }
+
+    pub fn from_bytes(bytes: &[u8], build_hasher: H) -> Result<Self, DecodeError> {
+        decode_exact(bytes, |reader| Self::read_from(reader, build_hasher))
+    }
+
+    pub fn read_from<R: Read>(reader: R, build_hasher: H) -> Result<Self, DecodeError> {
+        let counter_size = size_of::<C>();
+        let mut decoder = Decoder::new(reader, Kind::CountMinSketch)?;
+        if decoder.get_u64()? != counter_size as u64 {
+            return Err(DecodeError::ParameterMismatch("counter_size"));
+        }
+        let width = decoder.get_usize("width")?;
+        let depth = decoder.get_usize("depth")?;
+        decoder.expect_hasher(&build_hasher)?;
+        let payload = decoder.finish(
+            width
+                .checked_mul(depth)
+                .and_then(|size| size.checked_mul(counter_size)),
+        )?;
+
+        let mut cms = Self::try_new(width, depth, build_hasher)?;
+        cms.counters = payload
+            .chunks_exact(counter_size)
+            .map(|chunk| {
+                let mut bytes = [0; 16];
+                bytes[..counter_size].copy_from_slice(chunk);
+                <C as NumCast>::from(u128::from_le_bytes(bytes)).unwrap()
+            })
+            .collect();
+        Ok(cms)
+    }
+}
+
 impl<T, H, C> CountMinSketch<T, H, C>
 where
     T: Hash,
@@ -65,13 +149,8 @@ where
     H: BuildHasher,
 {
     pub fn count(&self, item: &T) -> C {
-        iter_hashes(item, &self.build_hasher)
-            .take(self.depth)
-            .enumerate()
-            .map(|(i, hash)| {
-                let idx = self.width * i + (hash as usize % self.width);
-                self.counters[idx].clone()
-            })
+        self.indices(item)
+            .map(|idx| self.counters[idx].clone())
             .min()
             .unwrap()
     }
@@ -83,6 +162,45 @@ where
             self.counters[idx] = self.counters[idx].saturating_add(count);
         }
     }
+
+    // Only raise counters up to the new estimate, which is never lower than the true count.
+    pub fn increment_conservative(&mut self, item: &T, count: &C) {
+        let indices = self.indices(item).collect::<Vec<_>>();
+        let estimate = indices
+            .iter()
+            .map(|&idx| &self.counters[idx])
+            .min()
+            .unwrap()
+            .saturating_add(count);
+        for idx in indices {
+            if self.counters[idx] < estimate {
+                self.counters[idx] = estimate.clone();
+            }
+        }
+    }
+
+    fn indices(&self, item: &T) -> impl Iterator<Item = usize> + '_ {
+        iter_hashes(item, &self.build_hasher)
+            .take(self.depth)
+            .enumerate()
+            .map(|(i, hash)| self.width * i + (hash as usize % self.width))
+    }
+}
+
+impl<T, H, C> Merge for CountMinSketch<T, H, C>
+where
+    C: SaturatingAdd,
+    H: BuildHasher,
+{
+    fn merge(&mut self, other: &Self) -> Result<(), IncompatibleSketch> {
+        ensure_eq(self.width, other.width, IncompatibleSketch::Width)?;
+        ensure_eq(self.depth, other.depth, IncompatibleSketch::Depth)?;
+        ensure_same_hasher(&self.build_hasher, &other.build_hasher)?;
+        for (counter, other) in self.counters.iter_mut().zip(&other.counters) {
+            *counter = counter.saturating_add(other);
+        }
+        Ok(())
+    }
 }
 
 impl<T, H, C> Debug for CountMinSketch<T, H, C> {
@@ -94,3 +212,115 @@ impl<T, H, C> Debug for CountMinSketch<T, H, C> {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use std::collections::HashMap;
+    use std::hash::{BuildHasherDefault, DefaultHasher};
+
+    fn make_sketch() -> CountMinSketch<u32, BuildHasherDefault<DefaultHasher>> {
+        CountMinSketch::new(200, 4, BuildHasherDefault::default())
+    }
+
+    fn zipf_stream(num_items: u32, exponent: f64, len: usize) -> Vec<u32> {
+        let weights = (1..=num_items)
+            .map(|rank| 1. / (rank as f64).powf(exponent))
+            .collect::<Vec<_>>();
+        let total = weights.iter().sum::<f64>();
+        let mut rng = StdRng::seed_from_u64(42);
+
+        (0..len)
+            .map(|_| {
+                let mut x = rng.gen::<f64>() * total;
+                weights
+                    .iter()
+                    .position(|&w| {
+                        x -= w;
+                        x <= 0.
+                    })
+                    .unwrap_or(num_items as usize - 1) as u32
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_try_with_error_bounds() {
+        let result = CountMinSketch::<u32, _>::try_with_error_bounds(
+            0.01,
+            1.,
+            BuildHasherDefault::<DefaultHasher>::default(),
+        );
+
+        assert_eq!(
+            result.err(),
+            Some(ParameterError::OutOfRange {
+                name: "delta",
+                range: "(0, 1)"
+            })
+        );
+    }
+
+    #[test]
+    fn test_try_with_error_bounds_overflow() {
+        let result = CountMinSketch::<u32, _>::try_with_error_bounds(
+            1e-300,
+            0.01,
+            BuildHasherDefault::<DefaultHasher>::default(),
+        );
+
+        assert_eq!(
+            result.err(),
+            Some(ParameterError::Overflow { name: "width" })
+        );
+    }
+
+    #[test]
+    fn test_increment() {
+        let mut cms = make_sketch();
+
+        cms.increment(&1, &3);
+        cms.increment(&1, &4);
+
+        assert_eq!(cms.count(&1), 7);
+        assert_eq!(cms.count(&2), 0);
+    }
+
+    #[test]
+    fn test_increment_conservative() {
+        let mut cms = make_sketch();
+
+        cms.increment_conservative(&1, &3);
+        cms.increment_conservative(&1, &4);
+
+        assert_eq!(cms.count(&1), 7);
+        assert_eq!(cms.count(&2), 0);
+    }
+
+    #[test]
+    fn test_conservative_error_on_zipfian_stream() {
+        let stream = zipf_stream(5000, 1.1, 100000);
+        let mut exact = HashMap::<u32, u32>::new();
+        let mut cms = make_sketch();
+        let mut conservative = make_sketch();
+
+        for item in &stream {
+            *exact.entry(*item).or_default() += 1;
+            cms.increment(item, &1);
+            conservative.increment_conservative(item, &1);
+        }
+
+        let mut error = 0;
+        let mut conservative_error = 0;
+        for (item, &count) in &exact {
+            assert!(cms.count(item) >= count);
+            assert!(conservative.count(item) >= count);
+            error += cms.count(item) - count;
+            conservative_error += conservative.count(item) - count;
+        }
+
+        assert!(conservative_error * 3 < error * 2);
+    }
+}
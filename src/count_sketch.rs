@@ -0,0 +1,377 @@
+use crate::error::{checked_usize, ensure, ensure_positive, ParameterError};
+use crate::hash::iter_hashes;
+use crate::merge::{ensure_eq, ensure_same_hasher, IncompatibleSketch, Merge};
+use num_traits::{PrimInt, Signed};
+use std::fmt::{Debug, Formatter};
+use std::hash::{BuildHasher, Hash};
+use std::marker::PhantomData;
+
+// Charikar, Chen and Farach-Colton, "Finding Frequent Items in Data Streams". Every row adds
+// the count to one bucket with a random sign, so colliding items cancel out in expectation
+// and each row gives an unbiased estimate. Counts may be negative.
+#[derive(Clone)]
+pub struct CountSketch<T, H, C = i64> {
+    counters: Vec<C>,
+    width: usize,
+    depth: usize,
+    build_hasher: H,
+    _phantom: PhantomData<T>,
+}
+
+impl<T, H, C> CountSketch<T, H, C>
+where
+    C: PrimInt + Signed,
+{
+    pub fn new(width: usize, depth: usize, build_hasher: H) -> Self {
+        Self::try_new(width, depth, build_hasher).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(width: usize, depth: usize, build_hasher: H) -> Result<Self, ParameterError> {
+        ensure_positive("width", width)?;
+        ensure_positive("depth", depth)?;
+        let size = width.checked_mul(depth).ok_or(ParameterError::Overflow {
+            name: "width * depth",
+        })?;
+        Ok(Self {
+            counters: vec![C::zero(); size],
+            width,
+            depth,
+            build_hasher,
+            _phantom: PhantomData,
+        })
+    }
+
+    pub fn with_error_bounds(epsilon: f64, delta: f64, build_hasher: H) -> Self {
+        Self::try_with_error_bounds(epsilon, delta, build_hasher)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    // Estimates are within `epsilon` times the L2 norm of the stream with probability
+    // at least `1 - delta`.
+    pub fn try_with_error_bounds(
+        epsilon: f64,
+        delta: f64,
+        build_hasher: H,
+    ) -> Result<Self, ParameterError> {
+        ensure(
+            0. < epsilon && epsilon <= 1.,
+            ParameterError::OutOfRange {
+                name: "epsilon",
+                range: "(0, 1]",
+            },
+        )?;
+        ensure(
+            0. < delta && delta < 1.,
+            ParameterError::OutOfRange {
+                name: "delta",
+                range: "(0, 1)",
+            },
+        )?;
+        let width = checked_usize("width", (3. / (epsilon * epsilon)).ceil())?;
+        let depth = (1. / delta).ln().ceil().max(1.) as usize;
+        Self::try_new(width, depth, build_hasher)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn clear(&mut self) {
+        self.counters.fill(C::zero());
+    }
+}
+
+impl<T, H, C> CountSketch<T, H, C>
+where
+    T: Hash,
+    C: PrimInt + Signed,
+    H: BuildHasher,
+{
+    pub fn increment(&mut self, item: &T, count: &C) {
+        for (idx, negative) in buckets(item, &self.build_hasher, self.width, self.depth) {
+            self.counters[idx] = if negative {
+                self.counters[idx].saturating_sub(*count)
+            } else {
+                self.counters[idx].saturating_add(*count)
+            };
+        }
+    }
+
+    pub fn estimate(&self, item: &T) -> C {
+        let mut estimates = buckets(item, &self.build_hasher, self.width, self.depth)
+            .map(|(idx, negative)| {
+                if negative {
+                    // A counter saturated at the minimum has no negation.
+                    C::zero().saturating_sub(self.counters[idx])
+                } else {
+                    self.counters[idx]
+                }
+            })
+            .collect::<Vec<_>>();
+        estimates.sort_unstable();
+        let mid = estimates.len() / 2;
+        if estimates.len() % 2 == 1 {
+            estimates[mid]
+        } else {
+            let (a, b) = (estimates[mid - 1], estimates[mid]);
+            let two = C::one() + C::one();
+            a / two + b / two + (a % two + b % two) / two
+        }
+    }
+}
+
+impl<T, H, C> CountSketch<T, H, C>
+where
+    C: PrimInt + Signed,
+    H: BuildHasher,
+{
+    // Subtracts the counts of `other`, so that the result sketches the difference between
+    // the two streams, for example two windows of traffic.
+    pub fn subtract(&mut self, other: &Self) -> Result<(), IncompatibleSketch> {
+        self.ensure_compatible(other)?;
+        for (counter, &other) in self.counters.iter_mut().zip(&other.counters) {
+            *counter = counter.saturating_sub(other);
+        }
+        Ok(())
+    }
+
+    // Estimates the inner product of the frequency vectors of the two streams.
+    pub fn inner_product(&self, other: &Self) -> Result<f64, IncompatibleSketch> {
+        self.ensure_compatible(other)?;
+        Ok(self.median_of_rows(|i| {
+            let row = i * self.width..(i + 1) * self.width;
+            self.counters[row.clone()]
+                .iter()
+                .zip(&other.counters[row])
+                .map(|(a, b)| a.to_f64().unwrap() * b.to_f64().unwrap())
+                .sum()
+        }))
+    }
+
+    // Estimates the L2 norm of the frequency vector.
+    pub fn l2(&self) -> f64 {
+        self.median_of_rows(|i| {
+            self.counters[i * self.width..(i + 1) * self.width]
+                .iter()
+                .map(|c| c.to_f64().unwrap().powi(2))
+                .sum()
+        })
+        .sqrt()
+    }
+
+    fn median_of_rows(&self, row_estimate: impl Fn(usize) -> f64) -> f64 {
+        let mut estimates = (0..self.depth).map(row_estimate).collect::<Vec<_>>();
+        estimates.sort_by(f64::total_cmp);
+        let mid = estimates.len() / 2;
+        if estimates.len() % 2 == 1 {
+            estimates[mid]
+        } else {
+            (estimates[mid - 1] + estimates[mid]) / 2.
+        }
+    }
+
+    fn ensure_compatible(&self, other: &Self) -> Result<(), IncompatibleSketch> {
+        ensure_eq(self.width, other.width, IncompatibleSketch::Width)?;
+        ensure_eq(self.depth, other.depth, IncompatibleSketch::Depth)?;
+        ensure_same_hasher(&self.build_hasher, &other.build_hasher)
+    }
+}
+
+impl<T, H, C> Merge for CountSketch<T, H, C>
+where
+    C: PrimInt + Signed,
+    H: BuildHasher,
+{
+    fn merge(&mut self, other: &Self) -> Result<(), IncompatibleSketch> {
+        self.ensure_compatible(other)?;
+        for (counter, &other) in self.counters.iter_mut().zip(&other.counters) {
+            *counter = counter.saturating_add(other);
+        }
+        Ok(())
+    }
+}
+
+// Each row bucket is taken from one hash of the `iter_hashes` family and its sign from the
+// top bit of the next. The lowest bit of `h1 + i * h2 + i^3` depends only on the lowest bits
+// of `h1` and `h2` and the parity of `i`, so it would repeat the same signs across rows,
+// while the top bit also depends on the carries out of every lower bit.
+fn buckets<'a, T, H>(
+    item: &'a T,
+    build_hasher: &'a H,
+    width: usize,
+    depth: usize,
+) -> impl Iterator<Item = (usize, bool)> + 'a
+where
+    T: Hash,
+    H: BuildHasher,
+{
+    let mut hashes = iter_hashes(item, build_hasher);
+    (0..depth).map(move |i| {
+        let bucket = hashes.next().unwrap() as usize % width;
+        let negative = hashes.next().unwrap() >> 31 == 1;
+        (width * i + bucket, negative)
+    })
+}
+
+impl<T, H, C> Debug for CountSketch<T, H, C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "CountSketch {{ width: {}, depth: {} }}",
+            self.width, self.depth
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use std::collections::HashMap;
+    use std::hash::{BuildHasherDefault, DefaultHasher};
+
+    type DefaultCountSketch = CountSketch<u32, BuildHasherDefault<DefaultHasher>>;
+
+    fn make_sketch() -> DefaultCountSketch {
+        CountSketch::new(500, 5, BuildHasherDefault::default())
+    }
+
+    fn random_counts(seed: u64) -> HashMap<u32, i64> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..2000).map(|i| (i, rng.gen_range(1..100))).collect()
+    }
+
+    fn sketch_counts(counts: &HashMap<u32, i64>) -> DefaultCountSketch {
+        let mut sketch = make_sketch();
+        for (item, count) in counts {
+            sketch.increment(item, count);
+        }
+        sketch
+    }
+
+    #[test]
+    fn test_try_with_error_bounds_overflow() {
+        let result =
+            DefaultCountSketch::try_with_error_bounds(1e-160, 0.01, BuildHasherDefault::default());
+
+        assert_eq!(
+            result.err(),
+            Some(ParameterError::Overflow { name: "width" })
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "depth must be > 0")]
+    fn test_depth_too_small() {
+        DefaultCountSketch::new(100, 0, BuildHasherDefault::default());
+    }
+
+    #[test]
+    fn test_negative_updates() {
+        let mut sketch = make_sketch();
+
+        sketch.increment(&1, &10);
+        sketch.increment(&2, &-7);
+        sketch.increment(&1, &-4);
+
+        assert_eq!(sketch.estimate(&1), 6);
+        assert_eq!(sketch.estimate(&2), -7);
+        assert_eq!(sketch.estimate(&3), 0);
+    }
+
+    #[test]
+    fn test_unbiased() {
+        let counts = random_counts(1);
+        let sketch = sketch_counts(&counts);
+
+        let total_error = counts
+            .iter()
+            .map(|(item, count)| sketch.estimate(item) - count)
+            .sum::<i64>();
+        let l2 = counts.values().map(|c| (c * c) as f64).sum::<f64>().sqrt();
+
+        // Per-item errors are of the order of `l2 / sqrt(width)`, but cancel out on average.
+        assert!((total_error as f64 / counts.len() as f64).abs() < 0.1 * l2 / 500f64.sqrt());
+    }
+
+    #[test]
+    fn test_l2_and_inner_product() {
+        let a_counts = random_counts(2);
+        let b_counts = random_counts(3);
+        let a = sketch_counts(&a_counts);
+        let b = sketch_counts(&b_counts);
+
+        let l2 = a_counts
+            .values()
+            .map(|c| (c * c) as f64)
+            .sum::<f64>()
+            .sqrt();
+        let inner_product = a_counts
+            .iter()
+            .map(|(item, count)| (count * b_counts[item]) as f64)
+            .sum::<f64>();
+
+        assert!((a.l2() - l2).abs() / l2 < 0.1);
+        assert!((a.inner_product(&b).unwrap() - inner_product).abs() / inner_product < 0.1);
+    }
+
+    #[test]
+    fn test_subtract_windows() {
+        let mut first = make_sketch();
+        let mut second = make_sketch();
+        for i in 0..1000 {
+            first.increment(&i, &10);
+            second.increment(&i, &10);
+        }
+        second.increment(&42, &500);
+        second.increment(&7, &-5);
+
+        second.subtract(&first).unwrap();
+
+        assert_eq!(second.estimate(&42), 500);
+        assert_eq!(second.estimate(&7), -5);
+        assert_eq!(second.estimate(&100), 0);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut a = make_sketch();
+        let mut b = make_sketch();
+        a.increment(&1, &3);
+        b.increment(&1, &4);
+
+        a.merge(&b).unwrap();
+
+        assert_eq!(a.estimate(&1), 7);
+        assert_eq!(
+            a.merge(&CountSketch::new(100, 5, BuildHasherDefault::default())),
+            Err(IncompatibleSketch::Width(500, 100))
+        );
+    }
+
+    #[test]
+    fn test_estimate_saturated_counter() {
+        // Whichever sign the item gets, its only counter saturates at 127 or -128.
+        let mut num_negative = 0;
+        for item in 0..20 {
+            let mut sketch = CountSketch::<u32, _, i8>::new(
+                1,
+                1,
+                BuildHasherDefault::<DefaultHasher>::default(),
+            );
+            sketch.increment(&item, &100);
+            sketch.increment(&item, &100);
+
+            assert_eq!(sketch.estimate(&item), 127);
+            if sketch.counters[0] == i8::MIN {
+                num_negative += 1;
+            }
+        }
+        assert!(num_negative > 0);
+    }
+}
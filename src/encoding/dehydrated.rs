@@ -0,0 +1,287 @@
+use crate::cardinality::hll::HyperLogLog;
+use crate::cardinality::linear_count::LinearCount;
+use crate::cms::CountMinSketch;
+use crate::encoding::DecodeError;
+use crate::set_membership::bloom::BloomFilter;
+use crate::set_membership::cuckoo::CuckooFilter;
+use num_traits::{PrimInt, Unsigned};
+use serde::de::{SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::{Debug, Formatter};
+use std::hash::BuildHasher;
+use std::marker::PhantomData;
+
+// Sketches are serialized as their binary encoding. Hashers and RNGs cannot be serialized,
+// so deserialization yields a `Dehydrated` sketch which is rehydrated with `with_hasher`.
+pub struct Dehydrated<S> {
+    bytes: Vec<u8>,
+    _phantom: PhantomData<S>,
+}
+
+impl<S> Dehydrated<S> {
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl<T, H> Dehydrated<BloomFilter<T, H>>
+where
+    H: BuildHasher,
+{
+    pub fn with_hasher(self, build_hasher: H) -> Result<BloomFilter<T, H>, DecodeError> {
+        BloomFilter::from_bytes(&self.bytes, build_hasher)
+    }
+}
+
+impl<T, const FINGERPRINT_SIZE: usize, H, R> Dehydrated<CuckooFilter<T, FINGERPRINT_SIZE, H, R>>
+where
+    H: BuildHasher,
+{
+    pub fn with_hasher_and_rng(
+        self,
+        build_hasher: H,
+        rng: R,
+    ) -> Result<CuckooFilter<T, FINGERPRINT_SIZE, H, R>, DecodeError> {
+        CuckooFilter::from_bytes(&self.bytes, build_hasher, rng)
+    }
+}
+
+impl<T, H> Dehydrated<HyperLogLog<T, H>>
+where
+    H: BuildHasher,
+{
+    pub fn with_hasher(self, build_hasher: H) -> Result<HyperLogLog<T, H>, DecodeError> {
+        HyperLogLog::from_bytes(&self.bytes, build_hasher)
+    }
+}
+
+impl<T, H> Dehydrated<LinearCount<T, H>>
+where
+    H: BuildHasher,
+{
+    pub fn with_hasher(self, build_hasher: H) -> Result<LinearCount<T, H>, DecodeError> {
+        LinearCount::from_bytes(&self.bytes, build_hasher)
+    }
+}
+
+impl<T, H, C> Dehydrated<CountMinSketch<T, H, C>>
+where
+    C: PrimInt + Unsigned,
+    H: BuildHasher,
+{
+    pub fn with_hasher(self, build_hasher: H) -> Result<CountMinSketch<T, H, C>, DecodeError> {
+        CountMinSketch::from_bytes(&self.bytes, build_hasher)
+    }
+}
+
+impl<S> Clone for Dehydrated<S> {
+    fn clone(&self) -> Self {
+        Self {
+            bytes: self.bytes.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<S> Debug for Dehydrated<S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Dehydrated {{ num_bytes: {} }}", self.bytes.len())
+    }
+}
+
+impl<S> Serialize for Dehydrated<S> {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        serializer.serialize_bytes(&self.bytes)
+    }
+}
+
+impl<'de, S> Deserialize<'de> for Dehydrated<S> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bytes = deserializer.deserialize_byte_buf(BytesVisitor)?;
+        Ok(Self {
+            bytes,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "an encoded sketch")
+    }
+
+    fn visit_bytes<E>(self, bytes: &[u8]) -> Result<Self::Value, E> {
+        Ok(bytes.to_vec())
+    }
+
+    fn visit_byte_buf<E>(self, bytes: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(bytes)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(bytes)
+    }
+}
+
+impl<T, H> Serialize for BloomFilter<T, H>
+where
+    H: BuildHasher,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.to_bytes())
+    }
+}
+
+impl<T, const FINGERPRINT_SIZE: usize, H, R> Serialize for CuckooFilter<T, FINGERPRINT_SIZE, H, R>
+where
+    H: BuildHasher,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.to_bytes())
+    }
+}
+
+impl<T, H> Serialize for HyperLogLog<T, H>
+where
+    H: BuildHasher,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.to_bytes())
+    }
+}
+
+impl<T, H> Serialize for LinearCount<T, H>
+where
+    H: BuildHasher,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.to_bytes())
+    }
+}
+
+impl<T, H, C> Serialize for CountMinSketch<T, H, C>
+where
+    C: PrimInt + Unsigned,
+    H: BuildHasher,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.to_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cardinality::Cardinality;
+    use crate::set_membership::SetMembership;
+    use rand::rngs::mock::StepRng;
+    use std::hash::{BuildHasherDefault, DefaultHasher};
+
+    type DefaultBuildHasher = BuildHasherDefault<DefaultHasher>;
+
+    #[test]
+    fn test_bloom_filter() {
+        let mut bf = BloomFilter::new(1000, 3, DefaultBuildHasher::default());
+        for i in 0..100 {
+            bf.insert(&i).unwrap();
+        }
+
+        let json = serde_json::to_string(&bf).unwrap();
+        let decoded = serde_json::from_str::<Dehydrated<BloomFilter<i32, _>>>(&json)
+            .unwrap()
+            .with_hasher(DefaultBuildHasher::default())
+            .unwrap();
+
+        assert!((0..100).all(|i| decoded.contains(&i)));
+    }
+
+    #[test]
+    fn test_cuckoo_filter() {
+        let mut cf = CuckooFilter::<_, 12, _, _>::new(
+            64,
+            4,
+            DefaultBuildHasher::default(),
+            StepRng::new(29, 97),
+        );
+        for i in 0..100 {
+            cf.insert(&i).unwrap();
+        }
+
+        let json = serde_json::to_string(&cf).unwrap();
+        let decoded = serde_json::from_str::<Dehydrated<CuckooFilter<i32, 12, _, _>>>(&json)
+            .unwrap()
+            .with_hasher_and_rng(DefaultBuildHasher::default(), StepRng::new(29, 97))
+            .unwrap();
+
+        assert!((0..100).all(|i| decoded.contains(&i)));
+    }
+
+    #[test]
+    fn test_hyperloglog() {
+        let mut hll = HyperLogLog::new(10, DefaultBuildHasher::default());
+        for i in 0..10000 {
+            hll.insert(&i);
+        }
+
+        let json = serde_json::to_string(&hll).unwrap();
+        let decoded = serde_json::from_str::<Dehydrated<HyperLogLog<i32, _>>>(&json)
+            .unwrap()
+            .with_hasher(DefaultBuildHasher::default())
+            .unwrap();
+
+        assert_eq!(decoded.count(), hll.count());
+    }
+
+    #[test]
+    fn test_linear_count() {
+        let mut lc = LinearCount::new(4096, DefaultBuildHasher::default());
+        for i in 0..500 {
+            lc.insert(&i);
+        }
+
+        let json = serde_json::to_string(&lc).unwrap();
+        let decoded = serde_json::from_str::<Dehydrated<LinearCount<i32, _>>>(&json)
+            .unwrap()
+            .with_hasher(DefaultBuildHasher::default())
+            .unwrap();
+
+        assert_eq!(decoded.count(), lc.count());
+    }
+
+    #[test]
+    fn test_count_min_sketch() {
+        let mut cms = CountMinSketch::<_, _, u64>::new(64, 4, DefaultBuildHasher::default());
+        for i in 0..100 {
+            cms.increment(&i, &(i as u64));
+        }
+
+        let json = serde_json::to_string(&cms).unwrap();
+        let dehydrated =
+            serde_json::from_str::<Dehydrated<CountMinSketch<i32, _, u64>>>(&json).unwrap();
+
+        assert_eq!(serde_json::to_string(&dehydrated).unwrap(), json);
+
+        let decoded = dehydrated
+            .with_hasher(DefaultBuildHasher::default())
+            .unwrap();
+
+        assert!((0..100).all(|i| decoded.count(&i) == cms.count(&i)));
+    }
+
+    #[test]
+    fn test_decode_error() {
+        let decoded = serde_json::from_str::<Dehydrated<HyperLogLog<i32, _>>>("[1, 2, 3]")
+            .unwrap()
+            .with_hasher(DefaultBuildHasher::default());
+
+        assert!(matches!(decoded, Err(DecodeError::Truncated)));
+    }
+}
@@ -0,0 +1,493 @@
+#[cfg(feature = "serde")]
+mod dehydrated;
+
+#[cfg(feature = "serde")]
+pub use dehydrated::Dehydrated;
+
+use crate::error::ParameterError;
+use crate::hash::hasher_fingerprint;
+use fixedbitset::FixedBitSet;
+use std::fmt::{Display, Formatter};
+use std::hash::BuildHasher;
+use std::io::{ErrorKind, Read};
+
+// Layout: magic, version, kind, kind-specific u64 parameters, hasher fingerprint,
+// payload length and payload, followed by a CRC-32 of all preceding bytes.
+// Integers are little-endian.
+const MAGIC: [u8; 4] = *b"PRBS";
+const VERSION: u8 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    BloomFilter = 1,
+    CuckooFilter = 2,
+    HyperLogLog = 3,
+    LinearCount = 4,
+    CountMinSketch = 5,
+}
+
+#[derive(Debug)]
+pub enum DecodeError {
+    Io(std::io::Error),
+    Truncated,
+    BadMagic,
+    UnsupportedVersion(u8),
+    WrongKind { expected: Kind, found: u8 },
+    InvalidParameter(ParameterError),
+    ParameterMismatch(&'static str),
+    ChecksumMismatch,
+    TrailingBytes,
+    InvalidPayload(&'static str),
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "io error: {}", err),
+            Self::Truncated => write!(f, "unexpected end of input"),
+            Self::BadMagic => write!(f, "bad magic bytes"),
+            Self::UnsupportedVersion(version) => write!(f, "unsupported version {}", version),
+            Self::WrongKind { expected, found } => {
+                write!(f, "expected {:?}, found kind {}", expected, found)
+            }
+            Self::InvalidParameter(err) => write!(f, "invalid parameter: {}", err),
+            Self::ParameterMismatch(name) => write!(f, "parameter mismatch: {}", name),
+            Self::ChecksumMismatch => write!(f, "checksum mismatch"),
+            Self::TrailingBytes => write!(f, "trailing bytes after sketch"),
+            Self::InvalidPayload(reason) => write!(f, "invalid payload: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::InvalidParameter(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParameterError> for DecodeError {
+    fn from(err: ParameterError) -> Self {
+        Self::InvalidParameter(err)
+    }
+}
+
+impl From<std::io::Error> for DecodeError {
+    fn from(err: std::io::Error) -> Self {
+        match err.kind() {
+            ErrorKind::UnexpectedEof => Self::Truncated,
+            _ => Self::Io(err),
+        }
+    }
+}
+
+pub(crate) struct Encoder {
+    buf: Vec<u8>,
+}
+
+impl Encoder {
+    pub fn new(kind: Kind) -> Self {
+        let mut buf = MAGIC.to_vec();
+        buf.extend([VERSION, kind as u8]);
+        Self { buf }
+    }
+
+    pub fn put_u64(mut self, value: u64) -> Self {
+        self.buf.extend(value.to_le_bytes());
+        self
+    }
+
+    pub fn put_hasher<H>(self, build_hasher: &H) -> Self
+    where
+        H: BuildHasher,
+    {
+        self.put_u64(hasher_fingerprint(build_hasher))
+    }
+
+    pub fn finish(self, payload: &[u8]) -> Vec<u8> {
+        let mut buf = self.put_u64(payload.len() as u64).buf;
+        buf.extend(payload);
+        buf.extend(crc32(&buf).to_le_bytes());
+        buf
+    }
+}
+
+pub(crate) struct Decoder<R> {
+    reader: R,
+    consumed: Vec<u8>,
+}
+
+impl<R> Decoder<R>
+where
+    R: Read,
+{
+    pub fn new(reader: R, kind: Kind) -> Result<Self, DecodeError> {
+        let mut decoder = Self {
+            reader,
+            consumed: Vec::new(),
+        };
+        if decoder.read_array::<4>()? != MAGIC {
+            return Err(DecodeError::BadMagic);
+        }
+        let [version, found] = decoder.read_array::<2>()?;
+        if version != VERSION {
+            return Err(DecodeError::UnsupportedVersion(version));
+        }
+        if found != kind as u8 {
+            return Err(DecodeError::WrongKind {
+                expected: kind,
+                found,
+            });
+        }
+        Ok(decoder)
+    }
+
+    pub fn get_u64(&mut self) -> Result<u64, DecodeError> {
+        Ok(u64::from_le_bytes(self.read_array()?))
+    }
+
+    pub fn get_usize(&mut self, name: &'static str) -> Result<usize, DecodeError> {
+        usize::try_from(self.get_u64()?).map_err(|_| DecodeError::ParameterMismatch(name))
+    }
+
+    pub fn expect_hasher<H>(&mut self, build_hasher: &H) -> Result<(), DecodeError>
+    where
+        H: BuildHasher,
+    {
+        if self.get_u64()? != hasher_fingerprint(build_hasher) {
+            return Err(DecodeError::ParameterMismatch("build_hasher"));
+        }
+        Ok(())
+    }
+
+    pub fn finish(mut self, payload_len: Option<usize>) -> Result<Vec<u8>, DecodeError> {
+        let len = self.get_u64()?;
+        if payload_len.is_none_or(|expected| expected as u64 != len) {
+            return Err(DecodeError::ParameterMismatch("payload length"));
+        }
+
+        // Read through `take` so a corrupted length cannot trigger a huge allocation.
+        let start = self.consumed.len();
+        (&mut self.reader)
+            .take(len)
+            .read_to_end(&mut self.consumed)?;
+        if (self.consumed.len() - start) as u64 != len {
+            return Err(DecodeError::Truncated);
+        }
+
+        let checksum = crc32(&self.consumed);
+        let payload = self.consumed.split_off(start);
+        if u32::from_le_bytes(self.read_array()?) != checksum {
+            return Err(DecodeError::ChecksumMismatch);
+        }
+        Ok(payload)
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N], DecodeError> {
+        let mut bytes = [0; N];
+        self.reader.read_exact(&mut bytes)?;
+        self.consumed.extend(bytes);
+        Ok(bytes)
+    }
+}
+
+pub(crate) fn decode_exact<T>(
+    mut bytes: &[u8],
+    read: impl FnOnce(&mut &[u8]) -> Result<T, DecodeError>,
+) -> Result<T, DecodeError> {
+    let value = read(&mut bytes)?;
+    if !bytes.is_empty() {
+        return Err(DecodeError::TrailingBytes);
+    }
+    Ok(value)
+}
+
+pub(crate) fn pack_bits(bits: &FixedBitSet) -> Vec<u8> {
+    let mut bytes = vec![0; bits.len().div_ceil(8)];
+    for index in bits.ones() {
+        bytes[index / 8] |= 1 << (index % 8);
+    }
+    bytes
+}
+
+pub(crate) fn unpack_bits(num_bits: usize, bytes: &[u8]) -> FixedBitSet {
+    let mut bits = FixedBitSet::with_capacity(num_bits);
+    for index in 0..num_bits {
+        bits.set(index, bytes[index / 8] & (1 << (index % 8)) != 0);
+    }
+    bits
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xedb88320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cardinality::hll::HyperLogLog;
+    use crate::cardinality::linear_count::LinearCount;
+    use crate::cardinality::Cardinality;
+    use crate::cms::CountMinSketch;
+    use crate::set_membership::bloom::BloomFilter;
+    use crate::set_membership::cuckoo::CuckooFilter;
+    use crate::set_membership::SetMembership;
+    use rand::rngs::mock::StepRng;
+    use std::hash::{BuildHasherDefault, DefaultHasher, RandomState};
+
+    type DefaultBuildHasher = BuildHasherDefault<DefaultHasher>;
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b"123456789"), 0xcbf43926);
+    }
+
+    #[test]
+    fn test_pack_bits() {
+        let mut bits = FixedBitSet::with_capacity(11);
+        bits.insert(0);
+        bits.insert(9);
+        bits.insert(10);
+
+        assert_eq!(pack_bits(&bits), vec![0b1, 0b110]);
+        assert_eq!(unpack_bits(11, &pack_bits(&bits)), bits);
+    }
+
+    #[test]
+    fn test_bloom_filter() {
+        let mut bf = BloomFilter::new(1000, 3, DefaultBuildHasher::default());
+        for i in 0..100 {
+            bf.insert(&i).unwrap();
+        }
+
+        let decoded =
+            BloomFilter::from_bytes(&bf.to_bytes(), DefaultBuildHasher::default()).unwrap();
+
+        assert_eq!(decoded.bits(), 1000);
+        assert_eq!(decoded.num_hashes(), 3);
+        assert!((0..100).all(|i| decoded.contains(&i)));
+    }
+
+    #[test]
+    fn test_cuckoo_filter() {
+        let mut cf = CuckooFilter::<_, 12, _, _>::new(
+            64,
+            4,
+            DefaultBuildHasher::default(),
+            StepRng::new(29, 97),
+        );
+        for i in 0..100 {
+            cf.insert(&i).unwrap();
+        }
+
+        let decoded = CuckooFilter::<i32, 12, _, _>::from_bytes(
+            &cf.to_bytes(),
+            DefaultBuildHasher::default(),
+            StepRng::new(29, 97),
+        )
+        .unwrap();
+
+        assert!((0..100).all(|i| decoded.contains(&i)));
+    }
+
+    #[test]
+    fn test_cuckoo_filter_fingerprint_size_mismatch() {
+        let cf = CuckooFilter::<i32, 12, _, _>::new(
+            64,
+            4,
+            DefaultBuildHasher::default(),
+            StepRng::new(29, 97),
+        );
+
+        let result = CuckooFilter::<i32, 8, _, _>::from_bytes(
+            &cf.to_bytes(),
+            DefaultBuildHasher::default(),
+            StepRng::new(29, 97),
+        );
+
+        assert!(matches!(
+            result,
+            Err(DecodeError::ParameterMismatch("fingerprint_size"))
+        ));
+    }
+
+    #[test]
+    fn test_hyperloglog() {
+        let mut hll = HyperLogLog::new(10, DefaultBuildHasher::default());
+        for i in 0..10000 {
+            hll.insert(&i);
+        }
+
+        let decoded =
+            HyperLogLog::<i32, _>::from_bytes(&hll.to_bytes(), DefaultBuildHasher::default())
+                .unwrap();
+
+        assert_eq!(decoded.precision(), 10);
+        assert_eq!(decoded.count(), hll.count());
+    }
+
+    #[test]
+    fn test_linear_count() {
+        let mut lc = LinearCount::new(4096, DefaultBuildHasher::default());
+        for i in 0..500 {
+            lc.insert(&i);
+        }
+
+        let decoded =
+            LinearCount::<i32, _>::from_bytes(&lc.to_bytes(), DefaultBuildHasher::default())
+                .unwrap();
+
+        assert_eq!(decoded.count(), lc.count());
+    }
+
+    #[test]
+    fn test_count_min_sketch() {
+        let mut cms = CountMinSketch::<_, _, u16>::new(64, 4, DefaultBuildHasher::default());
+        for i in 0..100 {
+            cms.increment(&i, &(i as u16 * 100));
+        }
+
+        let decoded = CountMinSketch::<i32, _, u16>::from_bytes(
+            &cms.to_bytes(),
+            DefaultBuildHasher::default(),
+        )
+        .unwrap();
+
+        assert!((0..100).all(|i| decoded.count(&i) == cms.count(&i)));
+    }
+
+    #[test]
+    fn test_count_min_sketch_counter_size_mismatch() {
+        let cms = CountMinSketch::<i32, _, u16>::new(64, 4, DefaultBuildHasher::default());
+
+        let result = CountMinSketch::<i32, _, u32>::from_bytes(
+            &cms.to_bytes(),
+            DefaultBuildHasher::default(),
+        );
+
+        assert!(matches!(
+            result,
+            Err(DecodeError::ParameterMismatch("counter_size"))
+        ));
+    }
+
+    #[test]
+    fn test_write_and_read() {
+        let mut hll = HyperLogLog::new(8, DefaultBuildHasher::default());
+        let mut lc = LinearCount::new(128, DefaultBuildHasher::default());
+        for i in 0..100 {
+            hll.insert(&i);
+            lc.insert(&i);
+        }
+
+        let mut buf = Vec::new();
+        hll.write_to(&mut buf).unwrap();
+        lc.write_to(&mut buf).unwrap();
+        let mut reader = buf.as_slice();
+
+        let hll_decoded =
+            HyperLogLog::<i32, _>::read_from(&mut reader, DefaultBuildHasher::default()).unwrap();
+        let lc_decoded =
+            LinearCount::<i32, _>::read_from(&mut reader, DefaultBuildHasher::default()).unwrap();
+
+        assert!(reader.is_empty());
+        assert_eq!(hll_decoded.count(), hll.count());
+        assert_eq!(lc_decoded.count(), lc.count());
+    }
+
+    #[test]
+    fn test_truncated() {
+        let bytes = BloomFilter::<i32, _>::new(1000, 3, DefaultBuildHasher::default()).to_bytes();
+
+        for len in 0..bytes.len() {
+            let result =
+                BloomFilter::<i32, _>::from_bytes(&bytes[..len], DefaultBuildHasher::default());
+            assert!(matches!(result, Err(DecodeError::Truncated)));
+        }
+    }
+
+    #[test]
+    fn test_trailing_bytes() {
+        let mut bytes =
+            BloomFilter::<i32, _>::new(1000, 3, DefaultBuildHasher::default()).to_bytes();
+        bytes.push(0);
+
+        let result = BloomFilter::<i32, _>::from_bytes(&bytes, DefaultBuildHasher::default());
+
+        assert!(matches!(result, Err(DecodeError::TrailingBytes)));
+    }
+
+    #[test]
+    fn test_bad_magic() {
+        let mut bytes =
+            BloomFilter::<i32, _>::new(1000, 3, DefaultBuildHasher::default()).to_bytes();
+        bytes[0] = b'X';
+
+        let result = BloomFilter::<i32, _>::from_bytes(&bytes, DefaultBuildHasher::default());
+
+        assert!(matches!(result, Err(DecodeError::BadMagic)));
+    }
+
+    #[test]
+    fn test_unsupported_version() {
+        let mut bytes =
+            BloomFilter::<i32, _>::new(1000, 3, DefaultBuildHasher::default()).to_bytes();
+        bytes[4] = VERSION + 1;
+
+        let result = BloomFilter::<i32, _>::from_bytes(&bytes, DefaultBuildHasher::default());
+
+        assert!(matches!(
+            result,
+            Err(DecodeError::UnsupportedVersion(v)) if v == VERSION + 1
+        ));
+    }
+
+    #[test]
+    fn test_wrong_kind() {
+        let bytes = HyperLogLog::<i32, _>::new(8, DefaultBuildHasher::default()).to_bytes();
+
+        let result = BloomFilter::<i32, _>::from_bytes(&bytes, DefaultBuildHasher::default());
+
+        assert!(matches!(
+            result,
+            Err(DecodeError::WrongKind {
+                expected: Kind::BloomFilter,
+                found: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn test_checksum_mismatch() {
+        let mut bytes =
+            BloomFilter::<i32, _>::new(1000, 3, DefaultBuildHasher::default()).to_bytes();
+        let len = bytes.len();
+        bytes[len - 10] ^= 1;
+
+        let result = BloomFilter::<i32, _>::from_bytes(&bytes, DefaultBuildHasher::default());
+
+        assert!(matches!(result, Err(DecodeError::ChecksumMismatch)));
+    }
+
+    #[test]
+    fn test_hasher_mismatch() {
+        let bytes = BloomFilter::<i32, _>::new(1000, 3, RandomState::new()).to_bytes();
+
+        let result = BloomFilter::<i32, _>::from_bytes(&bytes, RandomState::new());
+
+        assert!(matches!(
+            result,
+            Err(DecodeError::ParameterMismatch("build_hasher"))
+        ));
+    }
+}
@@ -0,0 +1,55 @@
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParameterError {
+    NotGreaterThan {
+        name: &'static str,
+        bound: usize,
+    },
+    NotPowerOfTwo {
+        name: &'static str,
+    },
+    OutOfRange {
+        name: &'static str,
+        range: &'static str,
+    },
+    Overflow {
+        name: &'static str,
+    },
+}
+
+impl Display for ParameterError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotGreaterThan { name, bound } => write!(f, "{} must be > {}", name, bound),
+            Self::NotPowerOfTwo { name } => write!(f, "{} must be a power of two", name),
+            Self::OutOfRange { name, range } => {
+                write!(f, "{} must be in the range {}", name, range)
+            }
+            Self::Overflow { name } => write!(f, "{} overflow", name),
+        }
+    }
+}
+
+impl std::error::Error for ParameterError {}
+
+pub(crate) fn ensure(condition: bool, error: ParameterError) -> Result<(), ParameterError> {
+    if condition {
+        Ok(())
+    } else {
+        Err(error)
+    }
+}
+
+pub(crate) fn ensure_positive(name: &'static str, value: usize) -> Result<(), ParameterError> {
+    ensure(value > 0, ParameterError::NotGreaterThan { name, bound: 0 })
+}
+
+// `as usize` saturates, so sizes derived from error bounds are checked before the cast.
+pub(crate) fn checked_usize(name: &'static str, value: f64) -> Result<usize, ParameterError> {
+    ensure(
+        value.is_finite() && value < usize::MAX as f64,
+        ParameterError::Overflow { name },
+    )?;
+    Ok(value as usize)
+}
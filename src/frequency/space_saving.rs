@@ -0,0 +1,320 @@
+use crate::error::{ensure_positive, ParameterError};
+use crate::merge::{ensure_eq, IncompatibleSketch, Merge};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::hash::Hash;
+
+struct Counter<T> {
+    item: Option<T>,
+    count: u64,
+    error: u64,
+}
+
+// Metwally et al., "Efficient Computation of Frequent and Top-k Elements in Data Streams".
+// Tracks `k` counters; an unmonitored item replaces the minimum counter and inherits its
+// count as overestimation error, so every count is off by at most `N / k`.
+pub struct SpaceSaving<T> {
+    // The stream summary: counters in ascending order of count, so that equal counts form
+    // contiguous blocks. Unused counters have no item and a count of zero.
+    counters: Vec<Counter<T>>,
+    // Index of the last counter of each block, keyed by the block's count.
+    block_ends: HashMap<u64, usize>,
+    positions: HashMap<T, usize>,
+    total: u64,
+}
+
+impl<T> SpaceSaving<T>
+where
+    T: Clone + Eq + Hash,
+{
+    pub fn new(k: usize) -> Self {
+        Self::try_new(k).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(k: usize) -> Result<Self, ParameterError> {
+        ensure_positive("k", k)?;
+        let mut summary = Self {
+            counters: Vec::new(),
+            block_ends: HashMap::new(),
+            positions: HashMap::with_capacity(k),
+            total: 0,
+        };
+        summary.rebuild(k, Vec::new());
+        Ok(summary)
+    }
+
+    pub fn k(&self) -> usize {
+        self.counters.len()
+    }
+
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    // The smallest monitored count, which bounds the error of every estimate and never
+    // exceeds `total / k`.
+    pub fn max_error(&self) -> u64 {
+        self.counters[0].count
+    }
+
+    pub fn insert(&mut self, item: &T) {
+        self.total += 1;
+        let index = match self.positions.get(item) {
+            Some(&index) => index,
+            None => {
+                let min = &mut self.counters[0];
+                if let Some(evicted) = min.item.replace(item.clone()) {
+                    self.positions.remove(&evicted);
+                }
+                min.error = min.count;
+                self.positions.insert(item.clone(), 0);
+                0
+            }
+        };
+        self.increment(index);
+    }
+
+    // Lower and upper bounds on the number of occurrences of `item`.
+    pub fn estimate(&self, item: &T) -> (u64, u64) {
+        match self.positions.get(item) {
+            Some(&index) => {
+                let counter = &self.counters[index];
+                (counter.count - counter.error, counter.count)
+            }
+            None => (0, self.max_error()),
+        }
+    }
+
+    // Monitored items with their bounds, in descending order of upper bound.
+    pub fn top_k(&self) -> Vec<(T, (u64, u64))> {
+        self.counters
+            .iter()
+            .rev()
+            .filter_map(|counter| {
+                let item = counter.item.as_ref()?;
+                Some((item.clone(), (counter.count - counter.error, counter.count)))
+            })
+            .collect()
+    }
+
+    // Every item occurring more than `phi * total` times is reported. Items whose lower
+    // bound also exceeds the threshold are guaranteed heavy hitters.
+    pub fn heavy_hitters(&self, phi: f64) -> Vec<(T, (u64, u64))> {
+        assert!(0. < phi && phi < 1., "phi must be in the range (0, 1)");
+        let threshold = phi * self.total as f64;
+        let mut top = self.top_k();
+        top.retain(|(_, (_, upper))| *upper as f64 > threshold);
+        top
+    }
+
+    fn count_and_error(&self, item: &T) -> (u64, u64) {
+        match self.positions.get(item) {
+            Some(&index) => (self.counters[index].count, self.counters[index].error),
+            None => (self.max_error(), self.max_error()),
+        }
+    }
+
+    fn increment(&mut self, index: usize) {
+        let count = self.counters[index].count;
+        let end = self.block_ends[&count];
+        self.counters.swap(index, end);
+        for i in [index, end] {
+            if let Some(item) = &self.counters[i].item {
+                *self.positions.get_mut(item).unwrap() = i;
+            }
+        }
+
+        if end > 0 && self.counters[end - 1].count == count {
+            self.block_ends.insert(count, end - 1);
+        } else {
+            self.block_ends.remove(&count);
+        }
+        self.counters[end].count += 1;
+        self.block_ends.entry(count + 1).or_insert(end);
+    }
+
+    fn rebuild(&mut self, k: usize, mut counters: Vec<Counter<T>>) {
+        counters.sort_by_key(|counter| Reverse(counter.count));
+        counters.truncate(k);
+        counters.resize_with(k, || Counter {
+            item: None,
+            count: 0,
+            error: 0,
+        });
+        counters.reverse();
+
+        self.positions.clear();
+        self.block_ends.clear();
+        for (index, counter) in counters.iter().enumerate() {
+            if let Some(item) = &counter.item {
+                self.positions.insert(item.clone(), index);
+            }
+            self.block_ends.insert(counter.count, index);
+        }
+        self.counters = counters;
+    }
+}
+
+// Agarwal et al., "Mergeable Summaries". An item missing from one summary may still have
+// occurred up to that summary's minimum count, which is added to its count and error.
+impl<T> Merge for SpaceSaving<T>
+where
+    T: Clone + Eq + Hash,
+{
+    fn merge(&mut self, other: &Self) -> Result<(), IncompatibleSketch> {
+        ensure_eq(self.k(), other.k(), IncompatibleSketch::K)?;
+        let items = self.positions.keys().chain(other.positions.keys());
+        let mut merged = HashMap::new();
+        for item in items {
+            merged.entry(item.clone()).or_insert_with(|| {
+                let (a_count, a_error) = self.count_and_error(item);
+                let (b_count, b_error) = other.count_and_error(item);
+                (a_count + b_count, a_error + b_error)
+            });
+        }
+
+        let counters = merged
+            .into_iter()
+            .map(|(item, (count, error))| Counter {
+                item: Some(item),
+                count,
+                error,
+            })
+            .collect();
+        self.total += other.total;
+        self.rebuild(self.k(), counters);
+        Ok(())
+    }
+}
+
+impl<T> Debug for SpaceSaving<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "SpaceSaving {{ k: {}, total: {} }}",
+            self.counters.len(),
+            self.total
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::distributions::Distribution;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    // Zipf-like stream where item `i` has weight proportional to `1 / (i + 1)`.
+    fn zipf_stream(n: usize, seed: u64) -> Vec<u32> {
+        let weights = (0..1000).map(|i| 1. / (i + 1) as f64).collect::<Vec<_>>();
+        let distribution = rand::distributions::WeightedIndex::new(&weights).unwrap();
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..n)
+            .map(|_| distribution.sample(&mut rng) as u32)
+            .collect()
+    }
+
+    fn exact_counts(stream: &[u32]) -> HashMap<u32, u64> {
+        let mut counts = HashMap::new();
+        for &item in stream {
+            *counts.entry(item).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    fn assert_bounds(summary: &SpaceSaving<u32>, counts: &HashMap<u32, u64>) {
+        for item in 0..1000 {
+            let exact = counts.get(&item).copied().unwrap_or(0);
+            let (lower, upper) = summary.estimate(&item);
+            assert!(lower <= exact && exact <= upper, "item {}", item);
+            assert!(upper - exact <= summary.max_error());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "k must be > 0")]
+    fn test_k_too_small() {
+        SpaceSaving::<u32>::new(0);
+    }
+
+    #[test]
+    fn test_exact_below_capacity() {
+        let mut summary = SpaceSaving::new(10);
+        for (item, count) in [(1, 5), (2, 3), (3, 1)] {
+            for _ in 0..count {
+                summary.insert(&item);
+            }
+        }
+
+        assert_eq!(summary.max_error(), 0);
+        assert_eq!(summary.estimate(&1), (5, 5));
+        assert_eq!(summary.estimate(&4), (0, 0));
+        assert_eq!(summary.top_k(), vec![(1, (5, 5)), (2, (3, 3)), (3, (1, 1))]);
+    }
+
+    #[test]
+    fn test_error_bounds() {
+        let stream = zipf_stream(100000, 1);
+        let counts = exact_counts(&stream);
+        let mut summary = SpaceSaving::new(50);
+        for item in &stream {
+            summary.insert(item);
+        }
+
+        assert_eq!(summary.total(), 100000);
+        assert!(summary.max_error() <= 100000 / 50);
+        assert_bounds(&summary, &counts);
+        assert_eq!(summary.top_k()[0].0, 0);
+    }
+
+    #[test]
+    fn test_heavy_hitters() {
+        let stream = zipf_stream(100000, 2);
+        let counts = exact_counts(&stream);
+        let mut summary = SpaceSaving::new(100);
+        for item in &stream {
+            summary.insert(item);
+        }
+        let reported = summary
+            .heavy_hitters(0.02)
+            .into_iter()
+            .map(|(item, _)| item)
+            .collect::<Vec<_>>();
+
+        for (item, &count) in &counts {
+            if count as f64 > 0.02 * 100000. {
+                assert!(reported.contains(item), "item {}", item);
+            }
+        }
+    }
+
+    #[test]
+    fn test_merge() {
+        let a_stream = zipf_stream(50000, 3);
+        let b_stream = zipf_stream(50000, 4);
+        let mut a = SpaceSaving::new(50);
+        let mut b = SpaceSaving::new(50);
+        for item in &a_stream {
+            a.insert(item);
+        }
+        for item in &b_stream {
+            b.insert(item);
+        }
+
+        a.merge(&b).unwrap();
+        let counts = exact_counts(&[a_stream, b_stream].concat());
+
+        assert_eq!(a.total(), 100000);
+        assert!(a.max_error() <= 100000 / 50);
+        assert_bounds(&a, &counts);
+        assert!(a.merge(&SpaceSaving::new(10)).is_err());
+
+        // The merged summary keeps working as a stream summary.
+        for _ in 0..10 {
+            a.insert(&5000);
+        }
+        assert!(a.estimate(&5000).1 >= 10);
+    }
+}
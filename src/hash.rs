@@ -15,6 +15,14 @@ where
     })
 }
 
+// `BuildHasher` has no notion of equality, so identify it by the hash of a fixed probe instead.
+pub(crate) fn hasher_fingerprint<H>(build_hasher: &H) -> u64
+where
+    H: BuildHasher,
+{
+    build_hasher.hash_one(0x9e3779b97f4a7c15u64)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,230 @@
+use crate::cms::CountMinSketch;
+use crate::error::{ensure_positive, ParameterError};
+use num_traits::{SaturatingAdd, ToPrimitive, Unsigned};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap};
+use std::fmt::{Debug, Formatter};
+use std::hash::{BuildHasher, Hash};
+
+pub struct HeavyHitters<T, H, C = u32> {
+    sketch: CountMinSketch<T, H, C>,
+    k: usize,
+    total: C,
+    candidates: HashMap<T, C>,
+    // Entries are not removed when a candidate's count changes, so the heap may hold
+    // stale entries. An entry is valid only if its count matches `candidates`.
+    heap: BinaryHeap<Reverse<Candidate<T, C>>>,
+}
+
+impl<T, H, C> HeavyHitters<T, H, C>
+where
+    C: Clone + Unsigned,
+{
+    pub fn new(k: usize, sketch: CountMinSketch<T, H, C>) -> Self {
+        Self::try_new(k, sketch).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(k: usize, sketch: CountMinSketch<T, H, C>) -> Result<Self, ParameterError> {
+        ensure_positive("k", k)?;
+        Ok(Self {
+            sketch,
+            k,
+            total: C::zero(),
+            candidates: HashMap::with_capacity(k + 1),
+            heap: BinaryHeap::with_capacity(2 * k),
+        })
+    }
+}
+
+impl<T, H, C> HeavyHitters<T, H, C> {
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    pub fn total(&self) -> &C {
+        &self.total
+    }
+
+    pub fn sketch(&self) -> &CountMinSketch<T, H, C> {
+        &self.sketch
+    }
+}
+
+impl<T, H, C> HeavyHitters<T, H, C>
+where
+    T: Clone + Eq + Hash,
+    C: Clone + Ord + SaturatingAdd + Unsigned,
+    H: BuildHasher,
+{
+    pub fn insert(&mut self, item: &T, count: &C) {
+        self.sketch.increment(item, count);
+        self.total = self.total.saturating_add(count);
+        let estimate = self.sketch.count(item);
+
+        if let Some(current) = self.candidates.get_mut(item) {
+            *current = estimate.clone();
+        } else if self.candidates.len() < self.k || self.pop_min_if_below(&estimate) {
+            self.candidates.insert(item.clone(), estimate.clone());
+        } else {
+            return;
+        }
+
+        self.heap.push(Reverse(Candidate {
+            count: estimate,
+            item: item.clone(),
+        }));
+        if self.heap.len() > 2 * self.k {
+            self.rebuild_heap();
+        }
+    }
+
+    pub fn top_k(&self) -> Vec<(T, C)> {
+        let mut top = self
+            .candidates
+            .iter()
+            .map(|(item, count)| (item.clone(), count.clone()))
+            .collect::<Vec<_>>();
+        top.sort_by(|(_, a), (_, b)| b.cmp(a));
+        top
+    }
+
+    fn pop_min_if_below(&mut self, count: &C) -> bool {
+        while let Some(Reverse(min)) = self.heap.peek() {
+            if self.candidates.get(&min.item) != Some(&min.count) {
+                self.heap.pop();
+                continue;
+            }
+            if min.count >= *count {
+                return false;
+            }
+            let Reverse(min) = self.heap.pop().unwrap();
+            self.candidates.remove(&min.item);
+            return true;
+        }
+        false
+    }
+
+    fn rebuild_heap(&mut self) {
+        self.heap = self
+            .candidates
+            .iter()
+            .map(|(item, count)| {
+                Reverse(Candidate {
+                    count: count.clone(),
+                    item: item.clone(),
+                })
+            })
+            .collect();
+    }
+}
+
+impl<T, H, C> HeavyHitters<T, H, C>
+where
+    T: Clone + Eq + Hash,
+    C: Clone + Ord + SaturatingAdd + ToPrimitive + Unsigned,
+    H: BuildHasher,
+{
+    pub fn heavy_hitters(&self, phi: f64) -> Vec<(T, C)> {
+        assert!(0. < phi && phi < 1., "phi must be in the range (0, 1)");
+        let threshold = phi * self.total.to_f64().unwrap();
+        let mut top = self.top_k();
+        top.retain(|(_, count)| count.to_f64().unwrap() >= threshold);
+        top
+    }
+}
+
+impl<T, H, C> Debug for HeavyHitters<T, H, C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "HeavyHitters {{ k: {}, sketch: {:?} }}",
+            self.k, self.sketch
+        )
+    }
+}
+
+struct Candidate<T, C> {
+    count: C,
+    item: T,
+}
+
+impl<T, C: Ord> Ord for Candidate<T, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.count.cmp(&other.count)
+    }
+}
+
+impl<T, C: Ord> PartialOrd for Candidate<T, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T, C: Ord> PartialEq for Candidate<T, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.count == other.count
+    }
+}
+
+impl<T, C: Ord> Eq for Candidate<T, C> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::hash::{BuildHasherDefault, DefaultHasher};
+
+    fn make_heavy_hitters(k: usize) -> HeavyHitters<u32, BuildHasherDefault<DefaultHasher>> {
+        HeavyHitters::new(
+            k,
+            CountMinSketch::new(1000, 4, BuildHasherDefault::default()),
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "k must be > 0")]
+    fn test_k_too_small() {
+        make_heavy_hitters(0);
+    }
+
+    #[test]
+    fn test_top_k() {
+        let mut hh = make_heavy_hitters(3);
+
+        for _ in 0..100 {
+            for i in 0..10 {
+                hh.insert(&i, &1);
+            }
+            hh.insert(&100, &3);
+            hh.insert(&101, &2);
+        }
+        let top = hh.top_k();
+
+        assert_eq!(top.len(), 3);
+        assert_eq!(top[..2], [(100, 300), (101, 200)]);
+        assert_eq!(top[2].1, 100);
+        assert_eq!(*hh.total(), 1500);
+    }
+
+    #[test]
+    fn test_heavy_hitters() {
+        let mut hh = make_heavy_hitters(5);
+
+        for i in 1000..2000 {
+            hh.insert(&i, &1);
+            hh.insert(&7, &1);
+            if i % 4 == 0 {
+                hh.insert(&42, &1);
+            }
+        }
+        let items = |phi| {
+            hh.heavy_hitters(phi)
+                .into_iter()
+                .map(|(item, _)| item)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(*hh.total(), 2250);
+        assert_eq!(items(0.1), vec![7, 42]);
+        assert_eq!(items(0.3), vec![7]);
+    }
+}
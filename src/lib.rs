@@ -1,7 +1,13 @@
-#![feature(unbounded_shifts)]
-
 mod bit_vec;
 pub mod cardinality;
 pub mod cms;
+pub mod count_sketch;
+pub mod encoding;
+pub mod error;
+pub mod frequency;
 mod hash;
+pub mod heavy_hitters;
+pub mod merge;
+pub mod quantiles;
 pub mod set_membership;
+pub mod similarity;
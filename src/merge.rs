@@ -0,0 +1,203 @@
+use crate::hash::hasher_fingerprint;
+use std::fmt::{Display, Formatter};
+use std::hash::BuildHasher;
+
+pub trait Merge {
+    fn merge(&mut self, other: &Self) -> Result<(), IncompatibleSketch>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IncompatibleSketch {
+    NumBits(usize, usize),
+    SignatureBits(usize, usize),
+    NumHashes(usize, usize),
+    Precision(usize, usize),
+    Width(usize, usize),
+    Depth(usize, usize),
+    FingerprintBits(usize, usize),
+    // Unlike the other variants, not a parameter mismatch: the sketches agree, but their
+    // combined contents do not fit in any compatible sketch, as (required, maximum).
+    Capacity(usize, usize),
+    K(usize, usize),
+    Hasher,
+}
+
+impl Display for IncompatibleSketch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NumBits(a, b) => write!(f, "num_bits differ: {} != {}", a, b),
+            Self::SignatureBits(a, b) => write!(f, "signature bits differ: {} != {}", a, b),
+            Self::NumHashes(a, b) => write!(f, "num_hashes differ: {} != {}", a, b),
+            Self::Precision(a, b) => write!(f, "precision differs: {} != {}", a, b),
+            Self::Width(a, b) => write!(f, "width differs: {} != {}", a, b),
+            Self::Depth(a, b) => write!(f, "depth differs: {} != {}", a, b),
+            Self::FingerprintBits(a, b) => write!(f, "fingerprint_bits differ: {} != {}", a, b),
+            Self::Capacity(a, b) => write!(f, "capacity exceeded: {} > {}", a, b),
+            Self::K(a, b) => write!(f, "k differs: {} != {}", a, b),
+            Self::Hasher => write!(f, "hashers differ"),
+        }
+    }
+}
+
+impl std::error::Error for IncompatibleSketch {}
+
+pub(crate) fn ensure_eq(
+    left: usize,
+    right: usize,
+    error: fn(usize, usize) -> IncompatibleSketch,
+) -> Result<(), IncompatibleSketch> {
+    if left == right {
+        Ok(())
+    } else {
+        Err(error(left, right))
+    }
+}
+
+pub(crate) fn ensure_same_hasher<H>(left: &H, right: &H) -> Result<(), IncompatibleSketch>
+where
+    H: BuildHasher,
+{
+    if hasher_fingerprint(left) == hasher_fingerprint(right) {
+        Ok(())
+    } else {
+        Err(IncompatibleSketch::Hasher)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cardinality::hll::HyperLogLog;
+    use crate::cardinality::linear_count::LinearCount;
+    use crate::cardinality::Cardinality;
+    use crate::cms::CountMinSketch;
+    use crate::set_membership::bloom::BloomFilter;
+    use crate::set_membership::SetMembership;
+    use std::hash::{BuildHasherDefault, DefaultHasher, RandomState};
+
+    type DefaultBuildHasher = BuildHasherDefault<DefaultHasher>;
+
+    #[test]
+    fn test_bloom_filter() {
+        let mut a = BloomFilter::new(1024, 3, DefaultBuildHasher::default());
+        let mut b = BloomFilter::new(1024, 3, DefaultBuildHasher::default());
+
+        for i in 0..50 {
+            a.insert(&i).unwrap();
+            b.insert(&(i + 50)).unwrap();
+        }
+        a.merge(&b).unwrap();
+
+        assert!((0..100).all(|i| a.contains(&i)));
+    }
+
+    #[test]
+    fn test_bloom_filter_incompatible() {
+        let mut a = BloomFilter::<i32, _>::new(1024, 3, DefaultBuildHasher::default());
+
+        assert_eq!(
+            a.merge(&BloomFilter::new(512, 3, DefaultBuildHasher::default())),
+            Err(IncompatibleSketch::NumBits(1024, 512))
+        );
+        assert_eq!(
+            a.merge(&BloomFilter::new(1024, 4, DefaultBuildHasher::default())),
+            Err(IncompatibleSketch::NumHashes(3, 4))
+        );
+    }
+
+    #[test]
+    fn test_bloom_filter_different_hasher() {
+        let mut a = BloomFilter::<i32, _>::new(1024, 3, RandomState::new());
+        let b = BloomFilter::new(1024, 3, RandomState::new());
+
+        assert_eq!(a.merge(&b), Err(IncompatibleSketch::Hasher));
+    }
+
+    #[test]
+    fn test_hyperloglog() {
+        let mut a = HyperLogLog::new(10, DefaultBuildHasher::default());
+        let mut b = HyperLogLog::new(10, DefaultBuildHasher::default());
+        let mut union = HyperLogLog::new(10, DefaultBuildHasher::default());
+
+        for i in 0..10000 {
+            a.insert(&i);
+            b.insert(&(i + 5000));
+            union.insert(&i);
+            union.insert(&(i + 5000));
+        }
+        a.merge(&b).unwrap();
+
+        assert_eq!(a.count(), union.count());
+    }
+
+    #[test]
+    fn test_hyperloglog_different_precision() {
+        let mut a = HyperLogLog::<i32, _>::new(10, DefaultBuildHasher::default());
+        let b = HyperLogLog::new(12, DefaultBuildHasher::default());
+
+        assert_eq!(a.merge(&b), Ok(()));
+        assert_eq!(a.precision(), 10);
+    }
+
+    #[test]
+    fn test_hyperloglog_different_hasher() {
+        let mut a = HyperLogLog::<i32, _>::new(10, RandomState::new());
+        let b = HyperLogLog::new(10, RandomState::new());
+
+        assert_eq!(a.merge(&b), Err(IncompatibleSketch::Hasher));
+    }
+
+    #[test]
+    fn test_linear_count() {
+        let mut a = LinearCount::new(4096, DefaultBuildHasher::default());
+        let mut b = LinearCount::new(4096, DefaultBuildHasher::default());
+        let mut union = LinearCount::new(4096, DefaultBuildHasher::default());
+
+        for i in 0..500 {
+            a.insert(&i);
+            b.insert(&(i + 250));
+            union.insert(&i);
+            union.insert(&(i + 250));
+        }
+        a.merge(&b).unwrap();
+
+        assert_eq!(a.count(), union.count());
+    }
+
+    #[test]
+    fn test_linear_count_incompatible() {
+        let mut a = LinearCount::<i32, _>::new(4096, DefaultBuildHasher::default());
+        let b = LinearCount::new(2048, DefaultBuildHasher::default());
+
+        assert_eq!(a.merge(&b), Err(IncompatibleSketch::NumBits(4096, 2048)));
+    }
+
+    #[test]
+    fn test_count_min_sketch() {
+        let mut a = CountMinSketch::<_, _, u8>::new(64, 4, DefaultBuildHasher::default());
+        let mut b = CountMinSketch::new(64, 4, DefaultBuildHasher::default());
+
+        a.increment(&1, &3);
+        a.increment(&2, &200);
+        b.increment(&1, &4);
+        b.increment(&2, &100);
+        a.merge(&b).unwrap();
+
+        assert!(a.count(&1) >= 7);
+        assert_eq!(a.count(&2), u8::MAX);
+    }
+
+    #[test]
+    fn test_count_min_sketch_incompatible() {
+        let mut a = CountMinSketch::<i32, _>::new(64, 4, DefaultBuildHasher::default());
+
+        assert_eq!(
+            a.merge(&CountMinSketch::new(32, 4, DefaultBuildHasher::default())),
+            Err(IncompatibleSketch::Width(64, 32))
+        );
+        assert_eq!(
+            a.merge(&CountMinSketch::new(64, 5, DefaultBuildHasher::default())),
+            Err(IncompatibleSketch::Depth(4, 5))
+        );
+    }
+}
@@ -0,0 +1,295 @@
+use crate::error::{ensure, ParameterError};
+use crate::merge::{IncompatibleSketch, Merge};
+use rand::Rng;
+use std::fmt::{Debug, Formatter};
+
+const MIN_LEVEL_CAPACITY: usize = 2;
+const CAPACITY_DECAY: f64 = 2. / 3.;
+
+// Karnin, Lang and Liberty, "Optimal Quantile Approximation in Streams". Items at level `h`
+// stand for `2^h` stream items. A full level is compacted by sorting it and promoting every
+// other item, starting at a random offset, so that rank estimates stay unbiased.
+pub struct KllSketch<T, R> {
+    k: usize,
+    levels: Vec<Vec<T>>,
+    len: u64,
+    rng: R,
+}
+
+impl<T, R> KllSketch<T, R> {
+    pub fn new(k: usize, rng: R) -> Self {
+        Self::try_new(k, rng).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(k: usize, rng: R) -> Result<Self, ParameterError> {
+        ensure(
+            (8..=65535).contains(&k),
+            ParameterError::OutOfRange {
+                name: "k",
+                range: "[8, 65535]",
+            },
+        )?;
+        Ok(Self {
+            k,
+            levels: vec![Vec::new()],
+            len: 0,
+            rng,
+        })
+    }
+
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn num_retained(&self) -> usize {
+        self.levels.iter().map(Vec::len).sum()
+    }
+
+    // Empirical bound from Apache DataSketches: with 99% confidence, every normalized rank
+    // estimate is within this distance of the true rank.
+    pub fn normalized_rank_error(&self) -> f64 {
+        2.296 / (self.k as f64).powf(0.9723)
+    }
+
+    // Levels below the top shrink geometrically, so lower levels are compacted more often.
+    fn capacity(&self, level: usize) -> usize {
+        let depth = self.levels.len() - level - 1;
+        let capacity = (self.k as f64 * CAPACITY_DECAY.powi(depth as i32)).ceil() as usize;
+        capacity.max(MIN_LEVEL_CAPACITY)
+    }
+
+    fn total_capacity(&self) -> usize {
+        (0..self.levels.len())
+            .map(|level| self.capacity(level))
+            .sum()
+    }
+}
+
+impl<T, R> KllSketch<T, R>
+where
+    T: Ord + Clone,
+    R: Rng,
+{
+    pub fn insert(&mut self, item: &T) {
+        self.levels[0].push(item.clone());
+        self.len += 1;
+        self.compress();
+    }
+
+    // Fraction of the stream less than or equal to `item`.
+    pub fn rank(&self, item: &T) -> f64 {
+        if self.len == 0 {
+            return 0.;
+        }
+        let weight = self
+            .levels
+            .iter()
+            .enumerate()
+            .map(|(level, items)| (items.iter().filter(|x| *x <= item).count() as u64) << level)
+            .sum::<u64>();
+        weight as f64 / self.len as f64
+    }
+
+    pub fn quantile(&self, q: f64) -> Option<T> {
+        assert!((0. ..=1.).contains(&q), "q must be in the range [0, 1]");
+        let mut weighted = self
+            .levels
+            .iter()
+            .enumerate()
+            .flat_map(|(level, items)| items.iter().map(move |item| (item, 1u64 << level)))
+            .collect::<Vec<_>>();
+        weighted.sort_by_key(|(item, _)| *item);
+
+        let target = (q * self.len as f64).ceil().max(1.) as u64;
+        let mut cumulative = 0;
+        for &(item, weight) in &weighted {
+            cumulative += weight;
+            if cumulative >= target {
+                return Some(item.clone());
+            }
+        }
+        weighted.last().map(|(item, _)| (*item).clone())
+    }
+
+    fn compress(&mut self) {
+        while self.num_retained() > self.total_capacity() {
+            let level = (0..self.levels.len())
+                .find(|&level| self.levels[level].len() >= self.capacity(level))
+                .unwrap();
+            if level + 1 == self.levels.len() {
+                self.levels.push(Vec::new());
+            }
+
+            let mut items = std::mem::take(&mut self.levels[level]);
+            items.sort_unstable();
+            if items.len() % 2 == 1 {
+                self.levels[level].push(items.pop().unwrap());
+            }
+            let offset = self.rng.gen_bool(0.5) as usize;
+            self.levels[level + 1].extend(items.into_iter().skip(offset).step_by(2));
+        }
+    }
+}
+
+impl<T, R> Merge for KllSketch<T, R>
+where
+    T: Ord + Clone,
+    R: Rng,
+{
+    // Sketches of different `k` are merged at the smaller one, whose error bound holds for
+    // the items summarized by the other.
+    fn merge(&mut self, other: &Self) -> Result<(), IncompatibleSketch> {
+        self.k = self.k.min(other.k);
+        while self.levels.len() < other.levels.len() {
+            self.levels.push(Vec::new());
+        }
+        for (level, items) in self.levels.iter_mut().zip(&other.levels) {
+            level.extend(items.iter().cloned());
+        }
+        self.len += other.len;
+        self.compress();
+        Ok(())
+    }
+}
+
+impl<T, R> Debug for KllSketch<T, R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "KllSketch {{ k: {}, len: {}, num_retained: {} }}",
+            self.k,
+            self.len,
+            self.num_retained()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+    use rand::SeedableRng;
+
+    fn make_sketch(k: usize, seed: u64) -> KllSketch<u32, StdRng> {
+        KllSketch::new(k, StdRng::seed_from_u64(seed))
+    }
+
+    fn shuffled(n: u32) -> Vec<u32> {
+        let mut values = (0..n).collect::<Vec<_>>();
+        values.shuffle(&mut StdRng::seed_from_u64(42));
+        values
+    }
+
+    #[test]
+    #[should_panic(expected = "k must be in the range [8, 65535]")]
+    fn test_k_too_small() {
+        make_sketch(4, 0);
+    }
+
+    #[test]
+    fn test_exact_below_capacity() {
+        let mut kll = make_sketch(200, 0);
+        for i in shuffled(100) {
+            kll.insert(&i);
+        }
+
+        assert_eq!(kll.num_retained(), 100);
+        assert_eq!(kll.rank(&49), 0.5);
+        assert_eq!(kll.quantile(0.), Some(0));
+        assert_eq!(kll.quantile(0.5), Some(49));
+        assert_eq!(kll.quantile(1.), Some(99));
+    }
+
+    #[test]
+    fn test_rank_error() {
+        let n = 100000;
+        let mut kll = make_sketch(200, 1);
+        for i in shuffled(n) {
+            kll.insert(&i);
+        }
+        let epsilon = kll.normalized_rank_error();
+
+        assert_eq!(kll.len(), n as u64);
+        assert!(kll.num_retained() < 3 * 200);
+        for item in (0..n).step_by(1000) {
+            let error = (kll.rank(&item) - (item + 1) as f64 / n as f64).abs();
+            assert!(error <= epsilon, "item {}: error {}", item, error);
+        }
+        for q in [0.01, 0.1, 0.5, 0.9, 0.99] {
+            let quantile = kll.quantile(q).unwrap();
+            assert!((quantile as f64 / n as f64 - q).abs() <= epsilon, "q {}", q);
+        }
+    }
+
+    #[test]
+    fn test_smaller_k_larger_error() {
+        assert!(
+            make_sketch(50, 0).normalized_rank_error()
+                > make_sketch(200, 0).normalized_rank_error()
+        );
+        assert!((make_sketch(200, 0).normalized_rank_error() - 0.0133).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_merge() {
+        let n = 100000;
+        let values = shuffled(n);
+        let mut sketches = values
+            .chunks(10000)
+            .enumerate()
+            .map(|(i, chunk)| {
+                let mut kll = make_sketch(200, i as u64);
+                for item in chunk {
+                    kll.insert(item);
+                }
+                kll
+            })
+            .collect::<Vec<_>>();
+        let mut merged = sketches.pop().unwrap();
+        for kll in &sketches {
+            merged.merge(kll).unwrap();
+        }
+        let epsilon = merged.normalized_rank_error();
+
+        assert_eq!(merged.len(), n as u64);
+        assert!(merged.num_retained() < 3 * 200);
+        for item in (0..n).step_by(1000) {
+            let error = (merged.rank(&item) - (item + 1) as f64 / n as f64).abs();
+            assert!(error <= epsilon, "item {}: error {}", item, error);
+        }
+    }
+
+    #[test]
+    fn test_merge_different_k() {
+        let n = 100000;
+        let mut large = make_sketch(400, 0);
+        let mut small = make_sketch(50, 1);
+        for (i, item) in shuffled(n).iter().enumerate() {
+            if i % 2 == 0 {
+                large.insert(item);
+            } else {
+                small.insert(item);
+            }
+        }
+
+        large.merge(&small).unwrap();
+        let epsilon = large.normalized_rank_error();
+
+        assert_eq!(large.k(), 50);
+        assert_eq!(epsilon, make_sketch(50, 0).normalized_rank_error());
+        assert!(large.num_retained() < 3 * 50);
+        for item in (0..n).step_by(1000) {
+            let error = (large.rank(&item) - (item + 1) as f64 / n as f64).abs();
+            assert!(error <= epsilon, "item {}: error {}", item, error);
+        }
+    }
+}
@@ -0,0 +1,2 @@
+pub mod kll;
+pub mod tdigest;
@@ -0,0 +1,433 @@
+use crate::error::{ensure, ParameterError};
+use crate::merge::{IncompatibleSketch, Merge};
+use std::borrow::Cow;
+use std::fmt::{Debug, Formatter};
+
+const BUFFER_FACTOR: f64 = 5.;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Centroid {
+    mean: f64,
+    weight: f64,
+}
+
+impl Centroid {
+    fn absorb(&mut self, other: &Centroid) {
+        self.weight += other.weight;
+        self.mean += (other.mean - self.mean) * other.weight / self.weight;
+    }
+}
+
+// Dunning, "Computing Extremely Accurate Quantiles Using t-Digests". The merging variant:
+// inserts are buffered and periodically merged into centroids whose size is bounded by the
+// logit scale function `k2`, so centroids near the tails stay small and the tails accurate.
+#[derive(Clone)]
+pub struct TDigest {
+    compression: f64,
+    centroids: Vec<Centroid>,
+    buffer: Vec<Centroid>,
+    min: f64,
+    max: f64,
+    // Alternating the direction of successive merges avoids biasing centroids to one side.
+    reverse_merge: bool,
+}
+
+impl TDigest {
+    pub fn new(compression: f64) -> Self {
+        Self::try_new(compression).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    // Higher compression keeps more centroids, at most `compression`, and improves
+    // accuracy accordingly.
+    pub fn try_new(compression: f64) -> Result<Self, ParameterError> {
+        ensure(
+            (10. ..=10000.).contains(&compression),
+            ParameterError::OutOfRange {
+                name: "compression",
+                range: "[10, 10000]",
+            },
+        )?;
+        Ok(Self {
+            compression,
+            centroids: Vec::new(),
+            buffer: Vec::with_capacity((BUFFER_FACTOR * compression) as usize),
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            reverse_merge: false,
+        })
+    }
+
+    pub fn compression(&self) -> f64 {
+        self.compression
+    }
+
+    pub fn num_centroids(&self) -> usize {
+        self.compressed().len()
+    }
+
+    pub fn total_weight(&self) -> f64 {
+        self.centroids
+            .iter()
+            .chain(&self.buffer)
+            .map(|c| c.weight)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.centroids.is_empty() && self.buffer.is_empty()
+    }
+
+    pub fn min(&self) -> Option<f64> {
+        (!self.is_empty()).then_some(self.min)
+    }
+
+    pub fn max(&self) -> Option<f64> {
+        (!self.is_empty()).then_some(self.max)
+    }
+
+    pub fn insert(&mut self, value: f64, weight: f64) {
+        // An infinite value would turn the interpolation between centroids into NaN.
+        assert!(value.is_finite(), "value must be finite");
+        assert!(weight > 0., "weight must be > 0");
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.buffer.push(Centroid {
+            mean: value,
+            weight,
+        });
+        if self.buffer.len() as f64 >= BUFFER_FACTOR * self.compression {
+            self.flush();
+        }
+    }
+
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        assert!((0. ..=1.).contains(&q), "q must be in the range [0, 1]");
+        let centroids = self.compressed();
+        let (first, last) = (centroids.first()?, centroids.last()?);
+        let total = centroids.iter().map(|c| c.weight).sum::<f64>();
+        let index = q * total;
+
+        if index < first.weight / 2. {
+            return Some(self.min + (first.mean - self.min) * index / (first.weight / 2.));
+        }
+        if index > total - last.weight / 2. {
+            return Some(self.max - (self.max - last.mean) * (total - index) / (last.weight / 2.));
+        }
+        let mut cumulative = first.weight / 2.;
+        for pair in centroids.windows(2) {
+            let step = (pair[0].weight + pair[1].weight) / 2.;
+            if cumulative + step >= index {
+                let t = (index - cumulative) / step;
+                return Some(pair[0].mean + t * (pair[1].mean - pair[0].mean));
+            }
+            cumulative += step;
+        }
+        Some(self.max)
+    }
+
+    pub fn cdf(&self, x: f64) -> Option<f64> {
+        let centroids = self.compressed();
+        let (first, last) = (centroids.first()?, centroids.last()?);
+        if x < self.min {
+            return Some(0.);
+        }
+        if x >= self.max {
+            return Some(1.);
+        }
+        let total = centroids.iter().map(|c| c.weight).sum::<f64>();
+
+        if x < first.mean {
+            let t = (x - self.min) / (first.mean - self.min);
+            return Some(t * first.weight / 2. / total);
+        }
+        if x > last.mean {
+            let t = (self.max - x) / (self.max - last.mean);
+            return Some(1. - t * last.weight / 2. / total);
+        }
+        let mut cumulative = first.weight / 2.;
+        for pair in centroids.windows(2) {
+            let step = (pair[0].weight + pair[1].weight) / 2.;
+            if x < pair[1].mean {
+                let t = (x - pair[0].mean) / (pair[1].mean - pair[0].mean);
+                return Some((cumulative + t * step) / total);
+            }
+            cumulative += step;
+        }
+        Some(1. - last.weight / 2. / total)
+    }
+
+    fn flush(&mut self) {
+        if !self.buffer.is_empty() {
+            self.centroids = self.merge_buffer(self.reverse_merge);
+            self.buffer.clear();
+            self.reverse_merge = !self.reverse_merge;
+        }
+    }
+
+    fn compressed(&self) -> Cow<'_, [Centroid]> {
+        if self.buffer.is_empty() {
+            Cow::Borrowed(&self.centroids)
+        } else {
+            Cow::Owned(self.merge_buffer(false))
+        }
+    }
+
+    // The scale function is symmetric, so a reverse merge runs the same pass over the
+    // centroids sorted in descending order.
+    fn merge_buffer(&self, reverse: bool) -> Vec<Centroid> {
+        let mut sorted = self
+            .centroids
+            .iter()
+            .chain(&self.buffer)
+            .copied()
+            .collect::<Vec<_>>();
+        sorted.sort_by(|a, b| a.mean.total_cmp(&b.mean));
+        if reverse {
+            sorted.reverse();
+        }
+        let total = sorted.iter().map(|c| c.weight).sum::<f64>();
+
+        let mut merged = Vec::with_capacity(self.compression as usize);
+        let mut current = sorted[0];
+        let mut weight_so_far = 0.;
+        // `k2(q) = δ / Z(n) * ln(q / (1 - q))` with Dunning's normalizer `Z(n) = 4 ln(n / δ) + 24`.
+        let normalizer = self.compression / (4. * (total / self.compression).max(1.).ln() + 24.);
+        let scale = |q: f64| normalizer * (q / (1. - q)).ln();
+        let mut k_lower = scale(0.);
+        for centroid in &sorted[1..] {
+            let q = (weight_so_far + current.weight + centroid.weight) / total;
+            if scale(q) - k_lower <= 1. {
+                current.absorb(centroid);
+            } else {
+                weight_so_far += current.weight;
+                k_lower = scale(weight_so_far / total);
+                merged.push(current);
+                current = *centroid;
+            }
+        }
+        merged.push(current);
+        if reverse {
+            merged.reverse();
+        }
+        merged
+    }
+}
+
+impl Merge for TDigest {
+    // Digests of different compression are merged at the lower one, since the centroids of
+    // the coarser digest cannot be refined.
+    fn merge(&mut self, other: &Self) -> Result<(), IncompatibleSketch> {
+        if other.compression < self.compression {
+            self.compression = other.compression;
+            let centroids = std::mem::take(&mut self.centroids);
+            self.buffer.extend(centroids);
+        }
+        if !other.is_empty() {
+            self.min = self.min.min(other.min);
+            self.max = self.max.max(other.max);
+            self.buffer
+                .extend(other.centroids.iter().chain(&other.buffer).copied());
+        }
+        self.flush();
+        Ok(())
+    }
+}
+
+impl Debug for TDigest {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "TDigest {{ compression: {}, num_centroids: {}, total_weight: {} }}",
+            self.compression,
+            self.num_centroids(),
+            self.total_weight()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+    use rand::{Rng, SeedableRng};
+
+    fn shuffled(n: usize) -> Vec<f64> {
+        let mut values = (0..n).map(|i| i as f64).collect::<Vec<_>>();
+        values.shuffle(&mut StdRng::seed_from_u64(42));
+        values
+    }
+
+    #[test]
+    #[should_panic(expected = "compression must be in the range [10, 10000]")]
+    fn test_compression_too_small() {
+        TDigest::new(1.);
+    }
+
+    #[test]
+    fn test_empty() {
+        let digest = TDigest::new(100.);
+
+        assert_eq!(digest.quantile(0.5), None);
+        assert_eq!(digest.cdf(0.), None);
+    }
+
+    #[test]
+    fn test_single_value() {
+        let mut digest = TDigest::new(100.);
+        digest.insert(3., 1.);
+
+        assert_eq!(digest.quantile(0.), Some(3.));
+        assert_eq!(digest.quantile(0.5), Some(3.));
+        assert_eq!(digest.quantile(1.), Some(3.));
+        assert_eq!(digest.cdf(2.), Some(0.));
+        assert_eq!(digest.cdf(3.), Some(1.));
+    }
+
+    #[test]
+    fn test_uniform_quantiles() {
+        let n = 100000;
+        let mut digest = TDigest::new(100.);
+        for value in shuffled(n) {
+            digest.insert(value, 1.);
+        }
+
+        assert_eq!(digest.quantile(0.), Some(0.));
+        assert_eq!(digest.quantile(1.), Some((n - 1) as f64));
+        for q in [0.0001, 0.001, 0.01, 0.1, 0.5, 0.9, 0.99, 0.999, 0.9999] {
+            let estimate = digest.quantile(q).unwrap();
+            // Tail quantiles must be accurate relative to their distance from the tail.
+            let tail = q.min(1. - q);
+            let error = (estimate / n as f64 - q).abs();
+            assert!(error < 0.1 * tail, "q {}: estimate {}", q, estimate);
+        }
+    }
+
+    #[test]
+    fn test_exponential_tails() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut digest = TDigest::new(200.);
+        let mut values = (0..100000)
+            .map(|_| -(1. - rng.gen::<f64>()).ln())
+            .collect::<Vec<_>>();
+        for &value in &values {
+            digest.insert(value, 1.);
+        }
+        values.sort_by(f64::total_cmp);
+
+        for q in [0.001, 0.5, 0.99, 0.999] {
+            let exact = values[(q * values.len() as f64) as usize];
+            let estimate = digest.quantile(q).unwrap();
+            assert!(
+                (estimate - exact).abs() / exact < 0.02,
+                "q {}: estimate {}, exact {}",
+                q,
+                estimate,
+                exact
+            );
+        }
+    }
+
+    #[test]
+    fn test_cdf() {
+        let mut digest = TDigest::new(100.);
+        for value in shuffled(10000) {
+            digest.insert(value, 1.);
+        }
+
+        assert_eq!(digest.cdf(-1.), Some(0.));
+        assert_eq!(digest.cdf(10000.), Some(1.));
+        for x in [10., 100., 5000., 9900., 9990.] {
+            let cdf = digest.cdf(x).unwrap();
+            assert!((cdf - x / 10000.).abs() < 0.002, "x {}: cdf {}", x, cdf);
+        }
+    }
+
+    #[test]
+    fn test_weights() {
+        let mut digest = TDigest::new(100.);
+        digest.insert(1., 99.);
+        digest.insert(100., 1.);
+
+        assert_eq!(digest.total_weight(), 100.);
+        assert_eq!(digest.quantile(0.25), Some(1.));
+        assert!(digest.quantile(0.75).unwrap() > 1.);
+    }
+
+    #[test]
+    fn test_compression_bounds_centroids() {
+        for compression in [20., 100., 500.] {
+            let mut digest = TDigest::new(compression);
+            for value in shuffled(100000) {
+                digest.insert(value, 1.);
+            }
+
+            assert!(digest.num_centroids() <= compression as usize);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "value must be finite")]
+    fn test_insert_infinite() {
+        TDigest::new(100.).insert(f64::INFINITY, 1.);
+    }
+
+    #[test]
+    fn test_merge_different_compression() {
+        let mut fine = TDigest::new(500.);
+        let mut coarse = TDigest::new(50.);
+        for value in shuffled(100000) {
+            fine.insert(value, 1.);
+            coarse.insert(value, 1.);
+        }
+
+        fine.merge(&coarse).unwrap();
+
+        assert_eq!(fine.compression(), 50.);
+        assert!(fine.num_centroids() <= 50);
+        assert_eq!(fine.total_weight(), 200000.);
+
+        let mut coarse = TDigest::new(50.);
+        let mut fine = TDigest::new(500.);
+        for value in shuffled(100000) {
+            fine.insert(value, 1.);
+        }
+        fine.merge(&coarse).unwrap();
+        coarse.merge(&TDigest::new(500.)).unwrap();
+
+        assert!(fine.num_centroids() <= 50);
+        assert_eq!(coarse.compression(), 50.);
+    }
+
+    #[test]
+    fn test_merge() {
+        let values = shuffled(100000);
+        let mut digests = values
+            .chunks(10000)
+            .map(|chunk| {
+                let mut digest = TDigest::new(100.);
+                for &value in chunk {
+                    digest.insert(value, 1.);
+                }
+                digest
+            })
+            .collect::<Vec<_>>();
+        let mut merged = digests.pop().unwrap();
+        for digest in &digests {
+            merged.merge(digest).unwrap();
+        }
+
+        assert_eq!(merged.total_weight(), 100000.);
+        assert_eq!(merged.min(), Some(0.));
+        assert_eq!(merged.max(), Some(99999.));
+        for q in [0.001, 0.01, 0.5, 0.99, 0.999] {
+            let estimate = merged.quantile(q).unwrap();
+            assert!(
+                (estimate / 100000. - q).abs() < 0.2 * q.min(1. - q),
+                "q {}: estimate {}",
+                q,
+                estimate
+            );
+        }
+    }
+}
@@ -0,0 +1,191 @@
+use crate::set_membership::xor::{build, mix, unique_hashes, ConstructionFailed, Fingerprint};
+use crate::set_membership::Membership;
+use std::borrow::Borrow;
+use std::fmt::{Debug, Formatter};
+use std::hash::{BuildHasher, Hash};
+use std::marker::PhantomData;
+
+const ARITY: usize = 3;
+const MAX_SEGMENT_LENGTH: usize = 1 << 18;
+
+pub type BinaryFuseFilter8<T, H> = BinaryFuseFilter<T, H, u8>;
+pub type BinaryFuseFilter16<T, H> = BinaryFuseFilter<T, H, u16>;
+
+// Graf and Lemire, "Binary Fuse Filters: Fast and Smaller Than Xor Filters". The three
+// positions of a key fall in consecutive segments, which lets the array shrink to about
+// 1.125 fingerprints per key for large key sets.
+pub struct BinaryFuseFilter<T, H, F = u8> {
+    fingerprints: Vec<F>,
+    segment_length: usize,
+    segment_count_length: usize,
+    seed: u64,
+    len: usize,
+    build_hasher: H,
+    _phantom: PhantomData<T>,
+}
+
+impl<T, H, F> BinaryFuseFilter<T, H, F>
+where
+    T: Hash,
+    H: BuildHasher,
+    F: Fingerprint,
+{
+    pub fn from_keys<I>(keys: I, build_hasher: H) -> Result<Self, ConstructionFailed>
+    where
+        I: IntoIterator,
+        I::Item: Borrow<T>,
+    {
+        let hashes = unique_hashes(keys, &build_hasher);
+        let size = hashes.len();
+
+        let segment_length = if size == 0 {
+            4
+        } else {
+            let exponent = ((size as f64).ln() / 3.33f64.ln() + 2.25).floor().max(0.) as u32;
+            (1usize << exponent.min(MAX_SEGMENT_LENGTH.trailing_zeros())).max(4)
+        };
+        let capacity = if size <= 1 {
+            0
+        } else {
+            let size_factor = f64::max(1.125, 0.875 + 0.25 * 1e6f64.ln() / (size as f64).ln());
+            (size as f64 * size_factor).round() as usize
+        };
+        let segment_count = capacity
+            .div_ceil(segment_length)
+            .saturating_sub(ARITY - 1)
+            .max(1);
+        let array_length = (segment_count + ARITY - 1) * segment_length;
+        let segment_count_length = segment_count * segment_length;
+
+        let (fingerprints, seed) = build(&hashes, array_length, |hash| {
+            positions(hash, segment_length, segment_count_length)
+        })?;
+        Ok(Self {
+            fingerprints,
+            segment_length,
+            segment_count_length,
+            seed,
+            len: size,
+            build_hasher,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+impl<T, H, F> BinaryFuseFilter<T, H, F> {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn num_fingerprints(&self) -> usize {
+        self.fingerprints.len()
+    }
+}
+
+impl<T, H, F> Membership<T> for BinaryFuseFilter<T, H, F>
+where
+    T: Hash,
+    H: BuildHasher,
+    F: Fingerprint,
+{
+    fn contains(&self, item: &T) -> bool {
+        let hash = mix(self.build_hasher.hash_one(item), self.seed);
+        let [h0, h1, h2] = positions(hash, self.segment_length, self.segment_count_length);
+        F::from_hash(hash) ^ self.fingerprints[h0] ^ self.fingerprints[h1] ^ self.fingerprints[h2]
+            == F::default()
+    }
+}
+
+impl<T, H, F> Debug for BinaryFuseFilter<T, H, F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "BinaryFuseFilter {{ fingerprint_size: {}, len: {}, num_fingerprints: {} }}",
+            8 * size_of::<F>(),
+            self.len,
+            self.fingerprints.len()
+        )
+    }
+}
+
+fn positions(hash: u64, segment_length: usize, segment_count_length: usize) -> [usize; 3] {
+    let mask = (segment_length - 1) as u64;
+    let h0 = ((hash as u128 * segment_count_length as u128) >> 64) as usize;
+    let h1 = h0 + segment_length;
+    let h2 = h1 + segment_length;
+    [
+        h0,
+        h1 ^ ((hash >> 18) & mask) as usize,
+        h2 ^ (hash & mask) as usize,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::hash::{BuildHasherDefault, DefaultHasher};
+
+    type DefaultBuildHasher = BuildHasherDefault<DefaultHasher>;
+
+    #[test]
+    fn test_contains_keys() {
+        for n in [1, 2, 10, 1000, 100000] {
+            let bf = BinaryFuseFilter8::<u32, _>::from_keys(0..n, DefaultBuildHasher::default())
+                .unwrap();
+
+            assert_eq!(bf.len(), n as usize);
+            assert!((0..n).all(|i| bf.contains(&i)));
+        }
+    }
+
+    #[test]
+    fn test_false_positive_rate() {
+        let bf8 = BinaryFuseFilter8::<u32, _>::from_keys(0..10000, DefaultBuildHasher::default())
+            .unwrap();
+        let bf16 = BinaryFuseFilter16::<u32, _>::from_keys(0..10000, DefaultBuildHasher::default())
+            .unwrap();
+
+        let fp8 = (10000..110000).filter(|i| bf8.contains(i)).count();
+        let fp16 = (10000..110000).filter(|i| bf16.contains(i)).count();
+
+        assert!(fp8 < 2 * 100000 / 256);
+        assert!(fp16 < 2 * 100000 / 65536 + 5);
+    }
+
+    #[test]
+    fn test_smaller_than_xor() {
+        use crate::set_membership::xor::XorFilter8;
+
+        let keys = (0..100000u32).collect::<Vec<_>>();
+        let bf =
+            BinaryFuseFilter8::<u32, _>::from_keys(&keys, DefaultBuildHasher::default()).unwrap();
+        let xf = XorFilter8::<u32, _>::from_keys(&keys, DefaultBuildHasher::default()).unwrap();
+
+        assert!(bf.num_fingerprints() < xf.num_fingerprints());
+        assert!(bf.num_fingerprints() < 120000);
+    }
+
+    #[test]
+    fn test_shared_membership_trait() {
+        use crate::set_membership::bloom::BloomFilter;
+        use crate::set_membership::SetMembership;
+
+        fn count_members<M: Membership<u32>>(filter: &M) -> usize {
+            (0..100).filter(|i| filter.contains(i)).count()
+        }
+
+        let mut bloom = BloomFilter::new(1000, 4, DefaultBuildHasher::default());
+        for i in 0..100 {
+            bloom.insert(&i).unwrap();
+        }
+        let bf =
+            BinaryFuseFilter8::<u32, _>::from_keys(0..100, DefaultBuildHasher::default()).unwrap();
+
+        assert_eq!(count_members(&bloom), 100);
+        assert_eq!(count_members(&bf), 100);
+    }
+}
@@ -1,10 +1,14 @@
+use crate::encoding::{decode_exact, pack_bits, unpack_bits, DecodeError, Decoder, Encoder, Kind};
+use crate::error::{checked_usize, ensure, ensure_positive, ParameterError};
 use crate::hash::iter_hashes;
+use crate::merge::{ensure_eq, ensure_same_hasher, IncompatibleSketch, Merge};
 use crate::set_membership::SetMembership;
 use fixedbitset::FixedBitSet;
 use std::convert::Infallible;
 use std::f64::consts::LN_2;
 use std::fmt::{Debug, Formatter};
 use std::hash::{BuildHasher, Hash};
+use std::io::{Read, Write};
 use std::marker::PhantomData;
 
 #[derive(Clone)]
@@ -17,25 +21,36 @@ pub struct BloomFilter<T, H> {
 
 impl<T, H> BloomFilter<T, H> {
     pub fn new(num_bits: usize, num_hashes: usize, build_hasher: H) -> Self {
-        assert!(num_bits > 0, "num_bits must be > 0");
-        assert!(num_hashes > 0, "num_hashes must be > 0");
-        Self {
+        Self::try_new(num_bits, num_hashes, build_hasher).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(
+        num_bits: usize,
+        num_hashes: usize,
+        build_hasher: H,
+    ) -> Result<Self, ParameterError> {
+        ensure_positive("num_bits", num_bits)?;
+        ensure_positive("num_hashes", num_hashes)?;
+        Ok(Self {
             bits: FixedBitSet::with_capacity(num_bits),
             num_hashes,
             build_hasher,
             _phantom: PhantomData,
-        }
+        })
     }
 
     pub fn with_probability(num_items: usize, probability: f64, build_hasher: H) -> Self {
-        assert!(num_items > 0, "num_items must be > 0");
-        assert!(
-            0. < probability && probability < 1.,
-            "probability must be in the range (0, 1)"
-        );
-        let bits = (-1. * num_items as f64 * probability / (LN_2 * LN_2)).ceil() as usize;
-        let num_hashes = (-1. * probability / LN_2).ceil() as usize;
-        Self::new(bits, num_hashes, build_hasher)
+        Self::try_with_probability(num_items, probability, build_hasher)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_with_probability(
+        num_items: usize,
+        probability: f64,
+        build_hasher: H,
+    ) -> Result<Self, ParameterError> {
+        let (bits, num_hashes) = optimal_size(num_items, probability)?;
+        Self::try_new(bits, num_hashes, build_hasher)
     }
 
     pub fn bits(&self) -> usize {
@@ -62,6 +77,59 @@ impl<T, H> BloomFilter<T, H> {
     }
 }
 
+pub(crate) fn optimal_size(
+    num_items: usize,
+    probability: f64,
+) -> Result<(usize, usize), ParameterError> {
+    ensure_positive("num_items", num_items)?;
+    ensure(
+        0. < probability && probability < 1.,
+        ParameterError::OutOfRange {
+            name: "probability",
+            range: "(0, 1)",
+        },
+    )?;
+    let bits = checked_usize(
+        "bits",
+        (-(num_items as f64) * probability.ln() / (LN_2 * LN_2)).ceil(),
+    )?;
+    let num_hashes = checked_usize("num_hashes", (-probability.ln() / LN_2).ceil())?;
+    Ok((bits, num_hashes))
+}
+
+impl<T, H> BloomFilter<T, H>
+where
+    H: BuildHasher,
+{
+    pub fn to_bytes(&self) -> Vec<u8> {
+        Encoder::new(Kind::BloomFilter)
+            .put_u64(self.bits.len() as u64)
+            .put_u64(self.num_hashes as u64)
+            .put_hasher(&self.build_hasher)
+            .finish(&pack_bits(&self.bits))
+    }
+
+    pub fn write_to<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+        writer.write_all(&self.to_bytes())
+    }
+
+    pub fn from_bytes(bytes: &[u8], build_hasher: H) -> Result<Self, DecodeError> {
+        decode_exact(bytes, |reader| Self::read_from(reader, build_hasher))
+    }
+
+    pub fn read_from<R: Read>(reader: R, build_hasher: H) -> Result<Self, DecodeError> {
+        let mut decoder = Decoder::new(reader, Kind::BloomFilter)?;
+        let num_bits = decoder.get_usize("num_bits")?;
+        let num_hashes = decoder.get_usize("num_hashes")?;
+        decoder.expect_hasher(&build_hasher)?;
+        let payload = decoder.finish(Some(num_bits.div_ceil(8)))?;
+
+        let mut filter = Self::try_new(num_bits, num_hashes, build_hasher)?;
+        filter.bits = unpack_bits(num_bits, &payload);
+        Ok(filter)
+    }
+}
+
 impl<T, H> SetMembership<T> for BloomFilter<T, H>
 where
     T: Hash,
@@ -83,6 +151,27 @@ where
     }
 }
 
+impl<T, H> Merge for BloomFilter<T, H>
+where
+    H: BuildHasher,
+{
+    fn merge(&mut self, other: &Self) -> Result<(), IncompatibleSketch> {
+        ensure_eq(
+            self.bits.len(),
+            other.bits.len(),
+            IncompatibleSketch::NumBits,
+        )?;
+        ensure_eq(
+            self.num_hashes,
+            other.num_hashes,
+            IncompatibleSketch::NumHashes,
+        )?;
+        ensure_same_hasher(&self.build_hasher, &other.build_hasher)?;
+        self.bits.union_with(&other.bits);
+        Ok(())
+    }
+}
+
 impl<T, H> Debug for BloomFilter<T, H> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -93,3 +182,47 @@ impl<T, H> Debug for BloomFilter<T, H> {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::hash::{BuildHasherDefault, DefaultHasher};
+
+    type DefaultBloomFilter = BloomFilter<u32, BuildHasherDefault<DefaultHasher>>;
+
+    #[test]
+    fn test_try_new() {
+        let result = DefaultBloomFilter::try_new(0, 3, BuildHasherDefault::default());
+
+        assert_eq!(
+            result.err(),
+            Some(ParameterError::NotGreaterThan {
+                name: "num_bits",
+                bound: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_try_with_probability() {
+        let out_of_range =
+            DefaultBloomFilter::try_with_probability(100, 0., BuildHasherDefault::default());
+        let overflow = DefaultBloomFilter::try_with_probability(
+            usize::MAX,
+            1e-10,
+            BuildHasherDefault::default(),
+        );
+
+        assert_eq!(
+            out_of_range.err(),
+            Some(ParameterError::OutOfRange {
+                name: "probability",
+                range: "(0, 1)"
+            })
+        );
+        assert_eq!(
+            overflow.err(),
+            Some(ParameterError::Overflow { name: "bits" })
+        );
+    }
+}
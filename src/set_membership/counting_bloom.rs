@@ -0,0 +1,224 @@
+use crate::bit_vec::BitVec;
+use crate::error::{ensure_positive, ParameterError};
+use crate::hash::iter_hashes;
+use crate::set_membership::bloom::optimal_size;
+use crate::set_membership::SetMembership;
+use std::fmt::{Debug, Formatter};
+use std::hash::{BuildHasher, Hash};
+use std::marker::PhantomData;
+
+const COUNTER_SIZE: usize = 4;
+const MAX_COUNT: u8 = (1 << COUNTER_SIZE) - 1;
+
+pub struct CountingBloomFilter<T, H> {
+    counters: BitVec<u8, COUNTER_SIZE>,
+    num_hashes: usize,
+    build_hasher: H,
+    _phantom: PhantomData<T>,
+}
+
+impl<T, H> CountingBloomFilter<T, H> {
+    pub fn new(num_counters: usize, num_hashes: usize, build_hasher: H) -> Self {
+        Self::try_new(num_counters, num_hashes, build_hasher)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(
+        num_counters: usize,
+        num_hashes: usize,
+        build_hasher: H,
+    ) -> Result<Self, ParameterError> {
+        ensure_positive("num_counters", num_counters)?;
+        ensure_positive("num_hashes", num_hashes)?;
+        Ok(Self {
+            counters: BitVec::try_new(num_counters)?,
+            num_hashes,
+            build_hasher,
+            _phantom: PhantomData,
+        })
+    }
+
+    pub fn with_probability(num_items: usize, probability: f64, build_hasher: H) -> Self {
+        Self::try_with_probability(num_items, probability, build_hasher)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_with_probability(
+        num_items: usize,
+        probability: f64,
+        build_hasher: H,
+    ) -> Result<Self, ParameterError> {
+        let (num_counters, num_hashes) = optimal_size(num_items, probability)?;
+        Self::try_new(num_counters, num_hashes, build_hasher)
+    }
+
+    pub fn num_counters(&self) -> usize {
+        self.counters.size()
+    }
+
+    pub fn num_hashes(&self) -> usize {
+        self.num_hashes
+    }
+
+    pub fn clear(&mut self) {
+        self.counters = BitVec::new(self.counters.size());
+    }
+}
+
+impl<T, H> CountingBloomFilter<T, H>
+where
+    T: Hash,
+    H: BuildHasher,
+{
+    pub fn count(&self, item: &T) -> u8 {
+        self.indices(item)
+            .into_iter()
+            .map(|index| self.counters.get(index))
+            .min()
+            .unwrap()
+    }
+
+    pub fn remove(&mut self, item: &T) -> bool {
+        let indices = self.indices(item);
+        if indices.iter().any(|&index| self.counters.get(index) == 0) {
+            return false;
+        }
+        for index in indices {
+            self.counters.set(index, self.counters.get(index) - 1);
+        }
+        true
+    }
+
+    // Different hashes may map to the same counter, which is then only incremented once.
+    fn indices(&self, item: &T) -> Vec<usize> {
+        let mut indices = iter_hashes(item, &self.build_hasher)
+            .take(self.num_hashes)
+            .map(|h| h as usize % self.counters.size())
+            .collect::<Vec<_>>();
+        indices.sort_unstable();
+        indices.dedup();
+        indices
+    }
+}
+
+impl<T, H> SetMembership<T> for CountingBloomFilter<T, H>
+where
+    T: Hash,
+    H: BuildHasher,
+{
+    type InsertError = CounterOverflow;
+
+    fn contains(&self, item: &T) -> bool {
+        self.count(item) > 0
+    }
+
+    fn insert(&mut self, item: &T) -> Result<(), Self::InsertError> {
+        let indices = self.indices(item);
+        if indices
+            .iter()
+            .any(|&index| self.counters.get(index) == MAX_COUNT)
+        {
+            return Err(CounterOverflow);
+        }
+        for index in indices {
+            self.counters.set(index, self.counters.get(index) + 1);
+        }
+        Ok(())
+    }
+}
+
+impl<T, H> Debug for CountingBloomFilter<T, H> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "CountingBloomFilter {{ num_counters: {}, num_hashes: {} }}",
+            self.counters.size(),
+            self.num_hashes
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CounterOverflow;
+
+impl std::fmt::Display for CounterOverflow {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "counter overflow")
+    }
+}
+
+impl std::error::Error for CounterOverflow {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::hash::{BuildHasherDefault, DefaultHasher};
+
+    fn make_filter(
+        num_counters: usize,
+        num_hashes: usize,
+    ) -> CountingBloomFilter<u32, BuildHasherDefault<DefaultHasher>> {
+        CountingBloomFilter::new(num_counters, num_hashes, BuildHasherDefault::default())
+    }
+
+    #[test]
+    fn test_contains_inserted() {
+        let mut cbf = make_filter(1000, 4);
+
+        for i in 0..100 {
+            cbf.insert(&i).unwrap();
+        }
+
+        assert!((0..100).all(|i| cbf.contains(&i)));
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut cbf = make_filter(1000, 4);
+
+        for i in 0..100 {
+            cbf.insert(&i).unwrap();
+        }
+        for i in 0..50 {
+            assert!(cbf.remove(&i));
+        }
+
+        assert!((50..100).all(|i| cbf.contains(&i)));
+        assert!((0..50).filter(|i| cbf.contains(i)).count() < 5);
+    }
+
+    #[test]
+    fn test_remove_missing() {
+        let mut cbf = make_filter(1000, 4);
+
+        cbf.insert(&1).unwrap();
+
+        assert!(!cbf.remove(&2));
+        assert!(cbf.contains(&1));
+    }
+
+    #[test]
+    fn test_count() {
+        let mut cbf = make_filter(1000, 4);
+
+        for _ in 0..3 {
+            cbf.insert(&1).unwrap();
+        }
+        cbf.remove(&1);
+
+        assert_eq!(cbf.count(&1), 2);
+        assert_eq!(cbf.count(&2), 0);
+    }
+
+    #[test]
+    fn test_counter_overflow() {
+        let mut cbf = make_filter(1000, 4);
+
+        for _ in 0..MAX_COUNT {
+            cbf.insert(&1).unwrap();
+        }
+
+        assert!(cbf.insert(&1).is_err());
+        assert_eq!(cbf.count(&1), MAX_COUNT);
+    }
+}
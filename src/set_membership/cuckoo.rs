@@ -1,10 +1,14 @@
 use crate::bit_vec::BitVec;
+use crate::encoding::{decode_exact, DecodeError, Decoder, Encoder, Kind};
+use crate::error::{ensure, ensure_positive, ParameterError};
+pub use crate::set_membership::NotEnoughSpace;
 use crate::set_membership::SetMembership;
 use rand::Rng;
 use std::hash::{BuildHasher, Hash};
+use std::io::{Read, Write};
 use std::marker::PhantomData;
 
-const MAX_EVICTIONS: u32 = 500;
+const MAX_EVICTIONS: usize = 500;
 
 pub struct CuckooFilter<T, const FINGERPRINT_SIZE: usize, H, R> {
     table: BitVec<u32, FINGERPRINT_SIZE>,
@@ -17,21 +21,85 @@ pub struct CuckooFilter<T, const FINGERPRINT_SIZE: usize, H, R> {
 
 impl<T, const FINGERPRINT_SIZE: usize, H, R> CuckooFilter<T, FINGERPRINT_SIZE, H, R> {
     pub fn new(num_buckets: usize, bucket_size: usize, build_hasher: H, rng: R) -> Self {
-        assert!(num_buckets > 1, "num_buckets must be > 1");
-        assert!(
-            num_buckets.is_power_of_two(),
-            "num_buckets must be a power of two"
-        );
-        assert!(bucket_size > 0, "bucket_size must be > 0");
+        Self::try_new(num_buckets, bucket_size, build_hasher, rng)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
 
-        Self {
-            table: BitVec::<u32, FINGERPRINT_SIZE>::new(num_buckets * bucket_size),
+    pub fn try_new(
+        num_buckets: usize,
+        bucket_size: usize,
+        build_hasher: H,
+        rng: R,
+    ) -> Result<Self, ParameterError> {
+        ensure(
+            num_buckets > 1,
+            ParameterError::NotGreaterThan {
+                name: "num_buckets",
+                bound: 1,
+            },
+        )?;
+        ensure(
+            num_buckets.is_power_of_two(),
+            ParameterError::NotPowerOfTwo {
+                name: "num_buckets",
+            },
+        )?;
+        ensure_positive("bucket_size", bucket_size)?;
+        let size = num_buckets
+            .checked_mul(bucket_size)
+            .ok_or(ParameterError::Overflow {
+                name: "num_buckets * bucket_size",
+            })?;
+
+        Ok(Self {
+            table: BitVec::<u32, FINGERPRINT_SIZE>::try_new(size)?,
             num_buckets,
             bucket_size,
             build_hasher,
             rng,
             _phantom: PhantomData,
+        })
+    }
+}
+
+impl<T, const FINGERPRINT_SIZE: usize, H, R> CuckooFilter<T, FINGERPRINT_SIZE, H, R>
+where
+    H: BuildHasher,
+{
+    pub fn to_bytes(&self) -> Vec<u8> {
+        Encoder::new(Kind::CuckooFilter)
+            .put_u64(FINGERPRINT_SIZE as u64)
+            .put_u64(self.num_buckets as u64)
+            .put_u64(self.bucket_size as u64)
+            .put_hasher(&self.build_hasher)
+            .finish(self.table.as_bytes())
+    }
+
+    pub fn write_to<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+        writer.write_all(&self.to_bytes())
+    }
+
+    pub fn from_bytes(bytes: &[u8], build_hasher: H, rng: R) -> Result<Self, DecodeError> {
+        decode_exact(bytes, |reader| Self::read_from(reader, build_hasher, rng))
+    }
+
+    pub fn read_from<Rd: Read>(reader: Rd, build_hasher: H, rng: R) -> Result<Self, DecodeError> {
+        let mut decoder = Decoder::new(reader, Kind::CuckooFilter)?;
+        if decoder.get_u64()? != FINGERPRINT_SIZE as u64 {
+            return Err(DecodeError::ParameterMismatch("fingerprint_size"));
         }
+        let num_buckets = decoder.get_usize("num_buckets")?;
+        let bucket_size = decoder.get_usize("bucket_size")?;
+        decoder.expect_hasher(&build_hasher)?;
+        let size = num_buckets.checked_mul(bucket_size);
+        let payload = decoder.finish(size.and_then(|size| {
+            size.checked_mul(FINGERPRINT_SIZE)
+                .map(|_| BitVec::<u32, FINGERPRINT_SIZE>::packed_len(size))
+        }))?;
+
+        let mut filter = Self::try_new(num_buckets, bucket_size, build_hasher, rng)?;
+        filter.table = BitVec::from_bytes(num_buckets * bucket_size, &payload);
+        Ok(filter)
     }
 }
 
@@ -54,11 +122,11 @@ where
         (index ^ (tag as usize).wrapping_mul(0x5bd1e995)) & (self.num_buckets - 1)
     }
 
-    fn contains_hashed(&self, i1: usize, i2: usize, tag: u32) -> bool {
-        [i1, i2].iter().any(|&index| {
+    fn find_hashed(&self, i1: usize, i2: usize, tag: u32) -> Option<usize> {
+        [i1, i2].into_iter().find_map(|index| {
             (0..self.bucket_size)
                 .map(|entry| index * self.bucket_size + entry)
-                .any(|address| self.table.get(address) == tag)
+                .find(|&address| self.table.get(address) == tag)
         })
     }
 
@@ -71,7 +139,7 @@ where
             .ok_or(())
     }
 
-    fn maybe_evict_and_insert(&mut self, index: usize, tag: u32) -> Option<u32> {
+    fn maybe_evict_and_insert(&mut self, index: usize, tag: u32) -> Option<(usize, u32)> {
         if self.try_insert(index, tag).is_ok() {
             return None;
         }
@@ -83,7 +151,17 @@ where
         debug_assert_ne!(old, 0, "evicted entry was 0");
         self.table.set(address, tag);
 
-        Some(old)
+        Some((address, old))
+    }
+
+    pub fn remove(&mut self, item: &T) -> bool {
+        let (i1, tag) = self.index_and_tag(item);
+        let i2 = self.alt_index(i1, tag);
+        debug_assert_eq!(i1, self.alt_index(i2, tag));
+
+        self.find_hashed(i1, i2, tag)
+            .inspect(|&address| self.table.set(address, 0))
+            .is_some()
     }
 }
 
@@ -101,27 +179,38 @@ where
         let i2 = self.alt_index(i1, tag);
         debug_assert_eq!(i1, self.alt_index(i2, tag));
 
-        self.contains_hashed(i1, i2, tag)
+        self.find_hashed(i1, i2, tag).is_some()
     }
 
     fn insert(&mut self, item: &T) -> Result<(), Self::InsertError> {
         let (i1, tag) = self.index_and_tag(item);
-        let i2 = self.alt_index(i1, tag);
-        debug_assert_eq!(i1, self.alt_index(i2, tag));
 
-        if self.contains_hashed(i1, i2, tag) || self.try_insert(i1, tag).is_ok() {
+        // Duplicates are stored as separate entries, otherwise removing one of two
+        // items sharing a fingerprint would remove both.
+        if self.try_insert(i1, tag).is_ok() {
             return Ok(());
         }
 
         let mut index = i1;
+        let mut tag = tag;
+        let mut evictions = [(0, 0); MAX_EVICTIONS];
 
-        for _ in 0..MAX_EVICTIONS {
+        for eviction in evictions.iter_mut() {
             index = self.alt_index(index, tag);
-            if self.maybe_evict_and_insert(index, tag).is_none() {
-                return Ok(());
+            match self.maybe_evict_and_insert(index, tag) {
+                None => return Ok(()),
+                Some((address, old)) => {
+                    *eviction = (address, old);
+                    tag = old;
+                }
             }
         }
 
+        // Roll back the evictions so the filter is left unchanged.
+        for &(address, old) in evictions.iter().rev() {
+            self.table.set(address, old);
+        }
+
         Err(NotEnoughSpace)
     }
 }
@@ -138,17 +227,6 @@ impl<T, const FINGERPRINT_SIZE: usize, H, R> std::fmt::Debug
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct NotEnoughSpace;
-
-impl std::fmt::Display for NotEnoughSpace {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "not enough space")
-    }
-}
-
-impl std::error::Error for NotEnoughSpace {}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -182,6 +260,20 @@ mod tests {
         make_filter::<4>(32, 0);
     }
 
+    #[test]
+    fn test_try_new() {
+        let build_hasher = BuildHasherDefault::<DefaultHasher>::default();
+        let rng = StepRng::new(29, 97);
+        let result = CuckooFilter::<i32, 4, _, _>::try_new(100, 10, build_hasher, rng);
+
+        assert_eq!(
+            result.err(),
+            Some(ParameterError::NotPowerOfTwo {
+                name: "num_buckets"
+            })
+        );
+    }
+
     #[test]
     fn test_contains_empty() {
         let cf = make_filter::<4>(64, 4);
@@ -209,5 +301,61 @@ mod tests {
         cf.insert(&2).unwrap();
 
         assert!(cf.insert(&3).is_err());
+        assert!(cf.contains(&1));
+        assert!(cf.contains(&2));
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut cf = make_filter::<8>(64, 4);
+
+        for i in 0..100 {
+            cf.insert(&i).unwrap();
+        }
+        for i in 0..50 {
+            assert!(cf.remove(&i));
+        }
+
+        assert!((50..100).all(|i| cf.contains(&i)));
+        assert!((0..50).filter(|i| cf.contains(i)).count() < 5);
+    }
+
+    #[test]
+    fn test_remove_missing() {
+        let mut cf = make_filter::<16>(64, 4);
+
+        cf.insert(&1).unwrap();
+
+        assert!(!cf.remove(&2));
+        assert!(cf.contains(&1));
+    }
+
+    #[test]
+    fn test_remove_duplicates() {
+        let mut cf = make_filter::<8>(64, 4);
+
+        cf.insert(&1).unwrap();
+        cf.insert(&1).unwrap();
+
+        assert!(cf.remove(&1));
+        assert!(cf.contains(&1));
+        assert!(cf.remove(&1));
+        assert!(!cf.contains(&1));
+        assert!(!cf.remove(&1));
+    }
+
+    #[test]
+    fn test_remove_after_evictions() {
+        let mut cf = make_filter::<16>(16, 2);
+        let inserted = (0..1000).take_while(|i| cf.insert(i).is_ok()).count() as i32;
+
+        assert!(inserted > 16);
+        assert!((0..inserted).all(|i| cf.contains(&i)));
+
+        for i in (0..inserted).step_by(2) {
+            assert!(cf.remove(&i));
+        }
+
+        assert!((1..inserted).step_by(2).all(|i| cf.contains(&i)));
     }
 }
@@ -1,6 +1,12 @@
+pub mod binary_fuse;
 pub mod bloom;
+pub mod counting_bloom;
 pub mod cuckoo;
 pub mod hash_set;
+pub mod quotient;
+pub mod ribbon;
+pub mod scalable_bloom;
+pub mod xor;
 
 pub trait SetMembership<T> {
     type InsertError;
@@ -8,3 +14,46 @@ pub trait SetMembership<T> {
     fn contains(&self, item: &T) -> bool;
     fn insert(&mut self, item: &T) -> Result<(), Self::InsertError>;
 }
+
+// Read-only view shared by every `SetMembership` and by static filters that are built once
+// from a key set and cannot be inserted into.
+pub trait Membership<T> {
+    fn contains(&self, item: &T) -> bool;
+}
+
+// Returned by filters with a fixed number of slots once an item cannot be placed.
+#[derive(Debug, Clone)]
+pub struct NotEnoughSpace;
+
+impl std::fmt::Display for NotEnoughSpace {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "not enough space")
+    }
+}
+
+impl std::error::Error for NotEnoughSpace {}
+
+// Every `SetMembership` is also a `Membership`. A blanket impl would conflict with the impls
+// of the static filters, so the dynamic ones are listed here.
+macro_rules! impl_membership {
+    ([$($generics:tt)*] $t:ty) => {
+        impl<T, $($generics)*> Membership<T> for $t
+        where
+            $t: SetMembership<T>,
+        {
+            fn contains(&self, item: &T) -> bool {
+                SetMembership::contains(self, item)
+            }
+        }
+    };
+}
+
+impl_membership!([H] bloom::BloomFilter<T, H>);
+impl_membership!([H] counting_bloom::CountingBloomFilter<T, H>);
+impl_membership!(
+    [const FINGERPRINT_SIZE: usize, H, R]
+    cuckoo::CuckooFilter<T, FINGERPRINT_SIZE, H, R>
+);
+impl_membership!([] std::collections::HashSet<T>);
+impl_membership!([H] quotient::QuotientFilter<T, H>);
+impl_membership!([H] scalable_bloom::ScalableBloomFilter<T, H>);
@@ -0,0 +1,644 @@
+use crate::error::{ensure, ensure_positive, ParameterError};
+use crate::merge::{ensure_eq, ensure_same_hasher, IncompatibleSketch, Merge};
+use crate::set_membership::{NotEnoughSpace, SetMembership};
+use std::fmt::{Debug, Formatter};
+use std::hash::{BuildHasher, Hash};
+use std::marker::PhantomData;
+
+// Each slot packs the remainder above three metadata bits.
+const OCCUPIED: u64 = 1;
+const CONTINUATION: u64 = 2;
+const SHIFTED: u64 = 4;
+const METADATA: u64 = OCCUPIED | CONTINUATION | SHIFTED;
+const METADATA_BITS: usize = 3;
+
+pub struct QuotientFilter<T, H> {
+    slots: Vec<u64>,
+    quotient_bits: usize,
+    remainder_bits: usize,
+    len: usize,
+    build_hasher: H,
+    _phantom: PhantomData<T>,
+}
+
+impl<T, H> QuotientFilter<T, H> {
+    pub fn new(quotient_bits: usize, remainder_bits: usize, build_hasher: H) -> Self {
+        Self::try_new(quotient_bits, remainder_bits, build_hasher)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(
+        quotient_bits: usize,
+        remainder_bits: usize,
+        build_hasher: H,
+    ) -> Result<Self, ParameterError> {
+        ensure_positive("quotient_bits", quotient_bits)?;
+        ensure_positive("remainder_bits", remainder_bits)?;
+        ensure(
+            remainder_bits <= 64 - METADATA_BITS,
+            ParameterError::OutOfRange {
+                name: "remainder_bits",
+                range: "[1, 61]",
+            },
+        )?;
+        ensure(
+            quotient_bits + remainder_bits <= 64 && quotient_bits < usize::BITS as usize,
+            ParameterError::Overflow {
+                name: "quotient_bits + remainder_bits",
+            },
+        )?;
+        ensure_allocatable(quotient_bits)?;
+        Ok(Self {
+            slots: vec![0; 1 << quotient_bits],
+            quotient_bits,
+            remainder_bits,
+            len: 0,
+            build_hasher,
+            _phantom: PhantomData,
+        })
+    }
+
+    pub fn quotient_bits(&self) -> usize {
+        self.quotient_bits
+    }
+
+    pub fn remainder_bits(&self) -> usize {
+        self.remainder_bits
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.slots.fill(0);
+        self.len = 0;
+    }
+
+    // Moves one bit from the remainder to the quotient, doubling the number of slots
+    // without access to the original items.
+    pub fn double(&mut self) -> Result<(), ParameterError> {
+        ensure(
+            self.remainder_bits > 1,
+            ParameterError::NotGreaterThan {
+                name: "remainder_bits",
+                bound: 1,
+            },
+        )?;
+        ensure_allocatable(self.quotient_bits + 1)?;
+        let fingerprints = self.fingerprints();
+        self.quotient_bits += 1;
+        self.remainder_bits -= 1;
+        self.slots = vec![0; 1 << self.quotient_bits];
+        self.rebuild(fingerprints);
+        Ok(())
+    }
+
+    fn incr(&self, index: usize) -> usize {
+        (index + 1) & (self.slots.len() - 1)
+    }
+
+    fn decr(&self, index: usize) -> usize {
+        index.wrapping_sub(1) & (self.slots.len() - 1)
+    }
+
+    fn is_occupied(&self, index: usize) -> bool {
+        self.slots[index] & OCCUPIED != 0
+    }
+
+    fn is_continuation(&self, index: usize) -> bool {
+        self.slots[index] & CONTINUATION != 0
+    }
+
+    fn is_shifted(&self, index: usize) -> bool {
+        self.slots[index] & SHIFTED != 0
+    }
+
+    fn is_empty_slot(&self, index: usize) -> bool {
+        self.slots[index] & METADATA == 0
+    }
+
+    fn remainder(&self, index: usize) -> u64 {
+        self.slots[index] >> METADATA_BITS
+    }
+
+    fn split(&self, fingerprint: u64) -> (usize, u64) {
+        let quotient = (fingerprint >> self.remainder_bits) as usize;
+        let remainder = fingerprint & ((1 << self.remainder_bits) - 1);
+        (quotient, remainder)
+    }
+
+    fn cluster_start(&self, quotient: usize) -> usize {
+        let mut index = quotient;
+        while self.is_shifted(index) {
+            index = self.decr(index);
+        }
+        index
+    }
+
+    fn run_start(&self, quotient: usize) -> usize {
+        let mut bucket = self.cluster_start(quotient);
+        let mut run = bucket;
+        while bucket != quotient {
+            loop {
+                run = self.incr(run);
+                if !self.is_continuation(run) {
+                    break;
+                }
+            }
+            loop {
+                bucket = self.incr(bucket);
+                if self.is_occupied(bucket) {
+                    break;
+                }
+            }
+        }
+        run
+    }
+
+    fn contains_fingerprint(&self, quotient: usize, remainder: u64) -> bool {
+        if !self.is_occupied(quotient) {
+            return false;
+        }
+        let mut index = self.run_start(quotient);
+        loop {
+            let current = self.remainder(index);
+            if current == remainder {
+                return true;
+            }
+            if current > remainder {
+                return false;
+            }
+            index = self.incr(index);
+            if !self.is_continuation(index) {
+                return false;
+            }
+        }
+    }
+
+    fn insert_fingerprint(
+        &mut self,
+        quotient: usize,
+        remainder: u64,
+    ) -> Result<(), NotEnoughSpace> {
+        if self.len == self.slots.len() {
+            return Err(NotEnoughSpace);
+        }
+        self.len += 1;
+
+        if self.is_empty_slot(quotient) {
+            self.slots[quotient] = (remainder << METADATA_BITS) | OCCUPIED;
+            return Ok(());
+        }
+
+        let was_occupied = self.is_occupied(quotient);
+        self.slots[quotient] |= OCCUPIED;
+        let start = self.run_start(quotient);
+        let mut index = start;
+
+        // Runs are kept sorted by remainder.
+        if was_occupied {
+            loop {
+                if self.remainder(index) > remainder {
+                    break;
+                }
+                index = self.incr(index);
+                if !self.is_continuation(index) {
+                    break;
+                }
+            }
+        }
+
+        let mut entry = remainder << METADATA_BITS;
+        if index != start {
+            entry |= CONTINUATION;
+        }
+        if index != quotient {
+            entry |= SHIFTED;
+        }
+        // The previous head of the run becomes a continuation of the new head.
+        let mut displaced_head = was_occupied && index == start;
+
+        loop {
+            let current = self.slots[index];
+            self.slots[index] = entry | (current & OCCUPIED);
+            if current & METADATA == 0 {
+                return Ok(());
+            }
+            entry = (current & !OCCUPIED) | SHIFTED;
+            if displaced_head {
+                entry |= CONTINUATION;
+                displaced_head = false;
+            }
+            index = self.incr(index);
+        }
+    }
+
+    fn remove_fingerprint(&mut self, quotient: usize, remainder: u64) -> bool {
+        if !self.contains_fingerprint(quotient, remainder) {
+            return false;
+        }
+
+        // Decode everything up to the next empty slot, clear it, and insert it back
+        // without the removed entry.
+        let start = self.cluster_start(quotient);
+        let mut entries = Vec::new();
+        let mut index = start;
+        let mut current = start;
+        while entries.len() < self.slots.len() && !self.is_empty_slot(index) {
+            current = self.quotient_of(index, current);
+            entries.push((current, self.remainder(index)));
+            index = self.incr(index);
+        }
+
+        let mut index = start;
+        for _ in 0..entries.len() {
+            self.slots[index] = 0;
+            index = self.incr(index);
+        }
+        self.len -= entries.len();
+
+        let position = entries
+            .iter()
+            .position(|&entry| entry == (quotient, remainder))
+            .unwrap();
+        entries.remove(position);
+        for (quotient, remainder) in entries {
+            self.insert_fingerprint(quotient, remainder).unwrap();
+        }
+        true
+    }
+
+    // Quotient of the entry in slot `index`, given the quotient of the entry before it.
+    fn quotient_of(&self, index: usize, previous: usize) -> usize {
+        if !self.is_shifted(index) {
+            return index;
+        }
+        if self.is_continuation(index) {
+            return previous;
+        }
+        let mut quotient = self.incr(previous);
+        while !self.is_occupied(quotient) {
+            quotient = self.incr(quotient);
+        }
+        quotient
+    }
+
+    fn fingerprints(&self) -> Vec<u64> {
+        let mut fingerprints = Vec::with_capacity(self.len);
+        let Some(start) =
+            (0..self.slots.len()).find(|&i| self.is_empty_slot(i) || !self.is_shifted(i))
+        else {
+            return fingerprints;
+        };
+
+        let mut index = start;
+        let mut quotient = start;
+        for _ in 0..self.slots.len() {
+            if !self.is_empty_slot(index) {
+                quotient = self.quotient_of(index, quotient);
+                fingerprints
+                    .push(((quotient as u64) << self.remainder_bits) | self.remainder(index));
+            }
+            index = self.incr(index);
+        }
+        fingerprints.sort_unstable();
+        fingerprints
+    }
+
+    fn rebuild(&mut self, fingerprints: impl IntoIterator<Item = u64>) {
+        self.clear();
+        for fingerprint in fingerprints {
+            let (quotient, remainder) = self.split(fingerprint);
+            self.insert_fingerprint(quotient, remainder).unwrap();
+        }
+    }
+}
+
+impl<T, H> QuotientFilter<T, H>
+where
+    T: Hash,
+    H: BuildHasher,
+{
+    pub fn remove(&mut self, item: &T) -> bool {
+        let (quotient, remainder) = self.quotient_and_remainder(item);
+        self.remove_fingerprint(quotient, remainder)
+    }
+
+    fn quotient_and_remainder(&self, item: &T) -> (usize, u64) {
+        let hash = self.build_hasher.hash_one(item);
+        self.split(hash >> (64 - self.quotient_bits - self.remainder_bits))
+    }
+}
+
+impl<T, H> SetMembership<T> for QuotientFilter<T, H>
+where
+    T: Hash,
+    H: BuildHasher,
+{
+    type InsertError = NotEnoughSpace;
+
+    fn contains(&self, item: &T) -> bool {
+        let (quotient, remainder) = self.quotient_and_remainder(item);
+        self.contains_fingerprint(quotient, remainder)
+    }
+
+    // Duplicates are stored as separate entries, so that removing one of two items
+    // sharing a fingerprint does not remove both.
+    fn insert(&mut self, item: &T) -> Result<(), Self::InsertError> {
+        let (quotient, remainder) = self.quotient_and_remainder(item);
+        self.insert_fingerprint(quotient, remainder)
+    }
+}
+
+impl<T, H> Merge for QuotientFilter<T, H>
+where
+    H: BuildHasher,
+{
+    // Filters with the same fingerprint width are compatible however it is split between
+    // quotient and remainder, so the result takes the larger quotient, doubled further as
+    // needed to fit both.
+    fn merge(&mut self, other: &Self) -> Result<(), IncompatibleSketch> {
+        let fingerprint_bits = self.quotient_bits + self.remainder_bits;
+        ensure_eq(
+            fingerprint_bits,
+            other.quotient_bits + other.remainder_bits,
+            IncompatibleSketch::FingerprintBits,
+        )?;
+        ensure_same_hasher(&self.build_hasher, &other.build_hasher)?;
+
+        // At least one remainder bit must be left.
+        let len = self.len + other.len;
+        let quotient_bits = (self.quotient_bits.max(other.quotient_bits)..fingerprint_bits)
+            .find(|&quotient_bits| len <= 1 << quotient_bits)
+            .ok_or(IncompatibleSketch::Capacity(
+                len,
+                1 << (fingerprint_bits - 1),
+            ))?;
+
+        // Fingerprints do not depend on the split, so both sorted lists can be merged as runs.
+        let mut left = self.fingerprints().into_iter().peekable();
+        let mut right = other.fingerprints().into_iter().peekable();
+        let mut merged = Vec::with_capacity(len);
+        while let (Some(&a), Some(&b)) = (left.peek(), right.peek()) {
+            if a <= b {
+                merged.push(a);
+                left.next();
+            } else {
+                merged.push(b);
+                right.next();
+            }
+        }
+        merged.extend(left);
+        merged.extend(right);
+
+        self.quotient_bits = quotient_bits;
+        self.remainder_bits = fingerprint_bits - quotient_bits;
+        self.slots = vec![0; 1 << quotient_bits];
+        self.rebuild(merged);
+        Ok(())
+    }
+}
+
+impl<T, H> Debug for QuotientFilter<T, H> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "QuotientFilter {{ quotient_bits: {}, remainder_bits: {}, len: {} }}",
+            self.quotient_bits, self.remainder_bits, self.len
+        )
+    }
+}
+
+// `Vec` cannot allocate more than `isize::MAX` bytes.
+fn ensure_allocatable(quotient_bits: usize) -> Result<(), ParameterError> {
+    ensure(
+        (1usize << quotient_bits)
+            .checked_mul(std::mem::size_of::<u64>())
+            .is_some_and(|bytes| bytes <= isize::MAX as usize),
+        ParameterError::Overflow {
+            name: "quotient_bits",
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use std::hash::{BuildHasherDefault, DefaultHasher};
+
+    fn make_filter(
+        quotient_bits: usize,
+        remainder_bits: usize,
+    ) -> QuotientFilter<u32, BuildHasherDefault<DefaultHasher>> {
+        QuotientFilter::new(quotient_bits, remainder_bits, BuildHasherDefault::default())
+    }
+
+    #[test]
+    #[should_panic(expected = "remainder_bits must be > 0")]
+    fn test_remainder_bits_too_small() {
+        make_filter(8, 0);
+    }
+
+    #[test]
+    fn test_allocation_overflow() {
+        let result = QuotientFilter::<u32, _>::try_new(
+            62,
+            2,
+            BuildHasherDefault::<DefaultHasher>::default(),
+        );
+
+        assert_eq!(
+            result.err(),
+            Some(ParameterError::Overflow {
+                name: "quotient_bits"
+            })
+        );
+    }
+
+    #[test]
+    fn test_contains_inserted() {
+        let mut qf = make_filter(8, 8);
+
+        for i in 0..230 {
+            qf.insert(&i).unwrap();
+        }
+
+        assert_eq!(qf.len(), 230);
+        assert!((0..230).all(|i| qf.contains(&i)));
+        assert!((1000..2000).filter(|i| qf.contains(i)).count() < 10);
+    }
+
+    #[test]
+    fn test_not_enough_space() {
+        let mut qf = make_filter(4, 8);
+
+        for i in 0..16 {
+            qf.insert(&i).unwrap();
+        }
+
+        assert!(qf.insert(&16).is_err());
+        assert!((0..16).all(|i| qf.contains(&i)));
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut qf = make_filter(8, 8);
+
+        for i in 0..200 {
+            qf.insert(&i).unwrap();
+        }
+        for i in (0..200).step_by(2) {
+            assert!(qf.remove(&i));
+        }
+
+        assert_eq!(qf.len(), 100);
+        assert!((1..200).step_by(2).all(|i| qf.contains(&i)));
+        assert!((0..200).step_by(2).filter(|i| qf.contains(i)).count() < 5);
+    }
+
+    #[test]
+    fn test_remove_duplicates() {
+        let mut qf = make_filter(8, 8);
+
+        qf.insert(&1).unwrap();
+        qf.insert(&1).unwrap();
+
+        assert!(qf.remove(&1));
+        assert!(qf.contains(&1));
+        assert!(qf.remove(&1));
+        assert!(!qf.contains(&1));
+        assert!(!qf.remove(&1));
+    }
+
+    #[test]
+    fn test_fingerprints_match_model() {
+        let mut qf = make_filter(6, 4);
+        let mut model = Vec::new();
+        let mut rng = StdRng::seed_from_u64(7);
+
+        for _ in 0..5000 {
+            let fingerprint = rng.gen_range(0..1 << 10);
+            let (quotient, remainder) = qf.split(fingerprint);
+            if rng.gen_bool(0.6) {
+                if qf.insert_fingerprint(quotient, remainder).is_ok() {
+                    model.push(fingerprint);
+                }
+            } else if let Some(position) = model.iter().position(|&f| f == fingerprint) {
+                assert!(qf.remove_fingerprint(quotient, remainder));
+                model.swap_remove(position);
+            } else {
+                assert!(!qf.remove_fingerprint(quotient, remainder));
+            }
+            model.sort_unstable();
+
+            assert_eq!(qf.fingerprints(), model);
+            assert!(model.iter().all(|&f| {
+                let (quotient, remainder) = qf.split(f);
+                qf.contains_fingerprint(quotient, remainder)
+            }));
+        }
+    }
+
+    #[test]
+    fn test_double() {
+        let mut qf = make_filter(6, 10);
+
+        for i in 0..60 {
+            qf.insert(&i).unwrap();
+        }
+        qf.double().unwrap();
+
+        assert_eq!(qf.capacity(), 128);
+        assert_eq!(qf.remainder_bits(), 9);
+        assert_eq!(qf.len(), 60);
+        assert!((0..60).all(|i| qf.contains(&i)));
+
+        for i in 60..120 {
+            qf.insert(&i).unwrap();
+        }
+
+        assert!((0..120).all(|i| qf.contains(&i)));
+    }
+
+    #[test]
+    fn test_double_remainder_exhausted() {
+        let mut qf = make_filter(6, 1);
+
+        assert!(qf.double().is_err());
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut a = make_filter(6, 10);
+        let mut b = make_filter(6, 10);
+
+        for i in 0..50 {
+            a.insert(&i).unwrap();
+            b.insert(&(i + 50)).unwrap();
+        }
+        a.merge(&b).unwrap();
+
+        assert_eq!(a.capacity(), 128);
+        assert_eq!(a.len(), 100);
+        assert!((0..100).all(|i| a.contains(&i)));
+    }
+
+    #[test]
+    fn test_merge_different_split() {
+        let mut a = make_filter(6, 10);
+        let mut b = make_filter(7, 9);
+
+        for i in 0..20 {
+            a.insert(&i).unwrap();
+        }
+        for i in 20..100 {
+            b.insert(&i).unwrap();
+        }
+        a.merge(&b).unwrap();
+
+        assert_eq!(a.quotient_bits(), 7);
+        assert_eq!(a.remainder_bits(), 9);
+        assert_eq!(a.len(), 100);
+        assert!((0..100).all(|i| a.contains(&i)));
+    }
+
+    #[test]
+    fn test_merge_incompatible() {
+        let mut a = make_filter(6, 10);
+
+        assert_eq!(
+            a.merge(&make_filter(7, 10)),
+            Err(IncompatibleSketch::FingerprintBits(16, 17))
+        );
+    }
+
+    #[test]
+    fn test_merge_capacity_exceeded_leaves_filter_unchanged() {
+        let mut a = make_filter(4, 2);
+        let mut b = make_filter(5, 1);
+
+        for i in 0..16 {
+            a.insert(&i).unwrap();
+        }
+        for i in 16..32 {
+            b.insert(&i).unwrap();
+        }
+        b.insert(&32).unwrap();
+
+        assert_eq!(a.merge(&b), Err(IncompatibleSketch::Capacity(33, 32)));
+        assert_eq!(a.capacity(), 16);
+        assert_eq!(a.len(), 16);
+        assert!((0..16).all(|i| a.contains(&i)));
+    }
+}
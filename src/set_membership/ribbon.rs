@@ -0,0 +1,235 @@
+use crate::error::{ensure, ParameterError};
+use crate::set_membership::xor::{mix, unique_hashes};
+use crate::set_membership::Membership;
+use std::fmt::{Debug, Formatter};
+use std::hash::{BuildHasher, Hash};
+use std::marker::PhantomData;
+
+const BAND_WIDTH: usize = 64;
+const SLOT_OVERHEAD: f64 = 0.08;
+const COEFFICIENT_SEED: u64 = 0x5bd1e9955bd1e995;
+const FREE_VARIABLE_SEED: u64 = 0x2545f4914f6cdd1d;
+
+// Dillinger and Walzer, "Ribbon filter: practically smarter than Bloom and Xor". Every key
+// contributes the equation `coefficients · S = 0` over a band of 64 consecutive slots, where
+// each slot of the solution `S` holds `fingerprint_bits` bits. In the homogeneous variant
+// banding cannot fail, and free variables are filled pseudo-randomly so that other keys
+// satisfy their equation with probability `2^-fingerprint_bits`.
+pub struct RibbonFilter<T, H> {
+    // Interleaved column-major storage: block `b` of 64 slots occupies words
+    // `b * fingerprint_bits..(b + 1) * fingerprint_bits`, one word per fingerprint bit.
+    solution: Vec<u64>,
+    num_slots: usize,
+    fingerprint_bits: usize,
+    len: usize,
+    build_hasher: H,
+    _phantom: PhantomData<T>,
+}
+
+impl<T, H> RibbonFilter<T, H>
+where
+    T: Hash,
+    H: BuildHasher,
+{
+    pub fn new(keys: &[T], fingerprint_bits: usize, build_hasher: H) -> Self {
+        Self::try_new(keys, fingerprint_bits, build_hasher).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(
+        keys: &[T],
+        fingerprint_bits: usize,
+        build_hasher: H,
+    ) -> Result<Self, ParameterError> {
+        ensure(
+            (1..=32).contains(&fingerprint_bits),
+            ParameterError::OutOfRange {
+                name: "fingerprint_bits",
+                range: "[1, 32]",
+            },
+        )?;
+        // Duplicate keys add nothing to the filter, but would inflate `len`.
+        let hashes = unique_hashes::<T, _, _>(keys, &build_hasher);
+        let num_slots = ((hashes.len() as f64 * (1. + SLOT_OVERHEAD)).ceil() as usize + BAND_WIDTH)
+            .next_multiple_of(BAND_WIDTH);
+
+        let mut coefficients = vec![0u64; num_slots];
+        for &hash in &hashes {
+            let (mut start, mut row) = band(hash, num_slots);
+            // Eliminate against existing pivots. A row reduced to zero is implied by the
+            // rows already banded, which is fine since every right-hand side is zero.
+            while row != 0 {
+                let shift = row.trailing_zeros();
+                start += shift as usize;
+                row >>= shift;
+                if coefficients[start] == 0 {
+                    coefficients[start] = row;
+                    break;
+                }
+                row ^= coefficients[start];
+            }
+        }
+
+        let mut filter = Self {
+            solution: vec![0; num_slots / BAND_WIDTH * fingerprint_bits],
+            num_slots,
+            fingerprint_bits,
+            len: hashes.len(),
+            build_hasher,
+            _phantom: PhantomData,
+        };
+        filter.back_substitute(&coefficients);
+        Ok(filter)
+    }
+
+    fn back_substitute(&mut self, coefficients: &[u64]) {
+        // `windows[j]` holds bit `j` of the solution for the 64 slots starting at `slot`.
+        let mut windows = vec![0u64; self.fingerprint_bits];
+        for slot in (0..self.num_slots).rev() {
+            let row = coefficients[slot];
+            let free = mix(slot as u64, FREE_VARIABLE_SEED);
+            for (j, window) in windows.iter_mut().enumerate() {
+                *window <<= 1;
+                let bit = if row == 0 {
+                    (free >> j) & 1
+                } else {
+                    ((*window & row).count_ones() & 1) as u64
+                };
+                *window |= bit;
+                let word = slot / BAND_WIDTH * self.fingerprint_bits + j;
+                self.solution[word] |= bit << (slot % BAND_WIDTH);
+            }
+        }
+    }
+}
+
+impl<T, H> RibbonFilter<T, H> {
+    pub fn fingerprint_bits(&self) -> usize {
+        self.fingerprint_bits
+    }
+
+    pub fn num_slots(&self) -> usize {
+        self.num_slots
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn bits_per_key(&self) -> f64 {
+        if self.len == 0 {
+            return 0.;
+        }
+        (self.num_slots * self.fingerprint_bits) as f64 / self.len as f64
+    }
+}
+
+impl<T, H> Membership<T> for RibbonFilter<T, H>
+where
+    T: Hash,
+    H: BuildHasher,
+{
+    fn contains(&self, item: &T) -> bool {
+        let (start, row) = band(self.build_hasher.hash_one(item), self.num_slots);
+        let block = start / BAND_WIDTH;
+        let offset = start % BAND_WIDTH;
+        (0..self.fingerprint_bits).all(|j| {
+            let mut window = self.solution[block * self.fingerprint_bits + j] >> offset;
+            if offset > 0 {
+                window |=
+                    self.solution[(block + 1) * self.fingerprint_bits + j] << (BAND_WIDTH - offset);
+            }
+            (window & row).count_ones() & 1 == 0
+        })
+    }
+}
+
+impl<T, H> Debug for RibbonFilter<T, H> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "RibbonFilter {{ fingerprint_bits: {}, num_slots: {}, len: {} }}",
+            self.fingerprint_bits, self.num_slots, self.len
+        )
+    }
+}
+
+// Maps a hash to the first slot of its band and the band's coefficients, whose lowest bit
+// is always set so that the band really starts at that slot.
+fn band(hash: u64, num_slots: usize) -> (usize, u64) {
+    let num_starts = num_slots - BAND_WIDTH + 1;
+    let start = ((hash as u128 * num_starts as u128) >> 64) as usize;
+    (start, mix(hash, COEFFICIENT_SEED) | 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::hash::{BuildHasherDefault, DefaultHasher};
+
+    fn make_filter(
+        keys: &[u32],
+        fingerprint_bits: usize,
+    ) -> RibbonFilter<u32, BuildHasherDefault<DefaultHasher>> {
+        RibbonFilter::new(keys, fingerprint_bits, BuildHasherDefault::default())
+    }
+
+    #[test]
+    #[should_panic(expected = "fingerprint_bits must be in the range [1, 32]")]
+    fn test_fingerprint_bits_too_small() {
+        make_filter(&[1, 2, 3], 0);
+    }
+
+    #[test]
+    fn test_contains_keys() {
+        let keys = (0..10000).collect::<Vec<_>>();
+
+        for fingerprint_bits in [1, 7, 16, 32] {
+            let rf = make_filter(&keys, fingerprint_bits);
+
+            assert!(keys.iter().all(|key| rf.contains(key)));
+        }
+    }
+
+    #[test]
+    fn test_false_positive_rate() {
+        let keys = (0..10000).collect::<Vec<_>>();
+
+        for fingerprint_bits in [4, 8, 12] {
+            let rf = make_filter(&keys, fingerprint_bits);
+            let expected = 100000. / (1 << fingerprint_bits) as f64;
+            let false_positives = (10000..110000).filter(|i| rf.contains(i)).count();
+
+            assert!(
+                (false_positives as f64) < 1.5 * expected + 10.,
+                "fingerprint_bits {}: {} false positives, expected {}",
+                fingerprint_bits,
+                false_positives,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_bits_per_key() {
+        let keys = (0..100000).collect::<Vec<_>>();
+        let rf = make_filter(&keys, 7);
+
+        assert!(rf.bits_per_key() < 7. * 1.1);
+    }
+
+    #[test]
+    fn test_duplicates_and_empty() {
+        let rf = make_filter(&[5, 5, 5, 6], 8);
+        let empty = make_filter(&[], 8);
+
+        assert!(rf.contains(&5) && rf.contains(&6));
+        assert_eq!(rf.len(), 2);
+        assert!(empty.is_empty());
+        assert_eq!(empty.bits_per_key(), 0.);
+        assert!((0..1000).filter(|i| empty.contains(i)).count() < 20);
+    }
+}
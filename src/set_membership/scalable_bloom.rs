@@ -0,0 +1,265 @@
+use crate::error::{ensure, ensure_positive, ParameterError};
+use crate::set_membership::bloom::BloomFilter;
+use crate::set_membership::SetMembership;
+use std::fmt::{Debug, Formatter};
+use std::hash::{BuildHasher, Hash};
+
+const DEFAULT_GROWTH_FACTOR: usize = 2;
+const DEFAULT_TIGHTENING_RATIO: f64 = 0.9;
+
+// Almeida et al., "Scalable Bloom Filters". Slice `i` holds `initial_capacity * s^i` items
+// with error `p * (1 - r) * r^i`, so the compound error stays below `p`.
+#[derive(Clone)]
+pub struct ScalableBloomFilter<T, H> {
+    slices: Vec<BloomFilter<T, H>>,
+    initial_capacity: usize,
+    probability: f64,
+    growth_factor: usize,
+    tightening_ratio: f64,
+    capacity: usize,
+    slice_len: usize,
+    len: usize,
+    build_hasher: H,
+}
+
+impl<T, H> ScalableBloomFilter<T, H>
+where
+    H: Clone,
+{
+    pub fn new(initial_capacity: usize, probability: f64, build_hasher: H) -> Self {
+        Self::try_new(initial_capacity, probability, build_hasher)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(
+        initial_capacity: usize,
+        probability: f64,
+        build_hasher: H,
+    ) -> Result<Self, ParameterError> {
+        Self::try_with_growth(
+            initial_capacity,
+            probability,
+            DEFAULT_GROWTH_FACTOR,
+            DEFAULT_TIGHTENING_RATIO,
+            build_hasher,
+        )
+    }
+
+    pub fn with_growth(
+        initial_capacity: usize,
+        probability: f64,
+        growth_factor: usize,
+        tightening_ratio: f64,
+        build_hasher: H,
+    ) -> Self {
+        Self::try_with_growth(
+            initial_capacity,
+            probability,
+            growth_factor,
+            tightening_ratio,
+            build_hasher,
+        )
+        .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_with_growth(
+        initial_capacity: usize,
+        probability: f64,
+        growth_factor: usize,
+        tightening_ratio: f64,
+        build_hasher: H,
+    ) -> Result<Self, ParameterError> {
+        ensure_positive("initial_capacity", initial_capacity)?;
+        ensure(
+            0. < probability && probability < 1.,
+            ParameterError::OutOfRange {
+                name: "probability",
+                range: "(0, 1)",
+            },
+        )?;
+        ensure_positive("growth_factor", growth_factor)?;
+        ensure(
+            0. < tightening_ratio && tightening_ratio < 1.,
+            ParameterError::OutOfRange {
+                name: "tightening_ratio",
+                range: "(0, 1)",
+            },
+        )?;
+        let mut filter = Self {
+            slices: Vec::new(),
+            initial_capacity,
+            probability,
+            growth_factor,
+            tightening_ratio,
+            capacity: 0,
+            slice_len: 0,
+            len: 0,
+            build_hasher,
+        };
+        filter.add_slice()?;
+        Ok(filter)
+    }
+
+    pub fn num_slices(&self) -> usize {
+        self.slices.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn false_positive_probability(&self) -> f64 {
+        1. - (0..self.slices.len())
+            .map(|i| 1. - self.slice_probability(i))
+            .product::<f64>()
+    }
+
+    fn slice_probability(&self, index: usize) -> f64 {
+        self.probability * (1. - self.tightening_ratio) * self.tightening_ratio.powi(index as i32)
+    }
+
+    // Fails once the slice error underflows to zero after many slices.
+    fn add_slice(&mut self) -> Result<(), ParameterError> {
+        let index = self.slices.len();
+        let capacity = self
+            .initial_capacity
+            .saturating_mul(self.growth_factor.saturating_pow(index as u32));
+        self.slices.push(BloomFilter::try_with_probability(
+            capacity,
+            self.slice_probability(index),
+            self.build_hasher.clone(),
+        )?);
+        self.capacity = capacity;
+        self.slice_len = 0;
+        Ok(())
+    }
+}
+
+impl<T, H> SetMembership<T> for ScalableBloomFilter<T, H>
+where
+    T: Hash,
+    H: BuildHasher + Clone,
+{
+    type InsertError = CapacityExhausted;
+
+    fn contains(&self, item: &T) -> bool {
+        self.slices.iter().any(|slice| slice.contains(item))
+    }
+
+    fn insert(&mut self, item: &T) -> Result<(), Self::InsertError> {
+        if self.contains(item) {
+            return Ok(());
+        }
+        if self.slice_len >= self.capacity {
+            self.add_slice().map_err(|_| CapacityExhausted)?;
+        }
+        let Ok(()) = self.slices.last_mut().unwrap().insert(item);
+        self.slice_len += 1;
+        self.len += 1;
+        Ok(())
+    }
+}
+
+impl<T, H> Debug for ScalableBloomFilter<T, H> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "ScalableBloomFilter {{ probability: {}, num_slices: {}, len: {} }}",
+            self.probability,
+            self.slices.len(),
+            self.len
+        )
+    }
+}
+
+// Returned by `insert` once no further slice can be added, e.g. when the error of the next
+// slice underflows to zero.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapacityExhausted;
+
+impl std::fmt::Display for CapacityExhausted {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "capacity exhausted")
+    }
+}
+
+impl std::error::Error for CapacityExhausted {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::hash::{BuildHasherDefault, DefaultHasher};
+
+    fn make_filter(
+        initial_capacity: usize,
+        probability: f64,
+    ) -> ScalableBloomFilter<u32, BuildHasherDefault<DefaultHasher>> {
+        ScalableBloomFilter::new(initial_capacity, probability, BuildHasherDefault::default())
+    }
+
+    #[test]
+    #[should_panic(expected = "probability must be in the range (0, 1)")]
+    fn test_probability_out_of_range() {
+        make_filter(100, 1.);
+    }
+
+    #[test]
+    fn test_grows() {
+        let mut sbf = make_filter(100, 0.01);
+
+        for i in 0..10000 {
+            sbf.insert(&i).unwrap();
+        }
+
+        assert_eq!(sbf.num_slices(), 7);
+        assert!(sbf.len() <= 10000);
+        assert!((0..10000).all(|i| sbf.contains(&i)));
+    }
+
+    #[test]
+    fn test_insert_fails_when_slice_probability_underflows() {
+        let mut sbf = ScalableBloomFilter::with_growth(
+            10,
+            0.5,
+            1,
+            1e-200,
+            BuildHasherDefault::<DefaultHasher>::default(),
+        );
+
+        let result = (0..100).try_for_each(|i| sbf.insert(&i));
+
+        assert_eq!(result, Err(CapacityExhausted));
+        assert_eq!(sbf.num_slices(), 2);
+        assert_eq!(sbf.len(), 20);
+    }
+
+    #[test]
+    fn test_duplicates_do_not_grow() {
+        let mut sbf = make_filter(100, 0.01);
+
+        for _ in 0..10 {
+            for i in 0..100 {
+                sbf.insert(&i).unwrap();
+            }
+        }
+
+        assert_eq!(sbf.num_slices(), 1);
+    }
+
+    #[test]
+    fn test_false_positive_probability() {
+        let mut sbf = make_filter(100, 0.01);
+
+        for i in 0..10000 {
+            sbf.insert(&i).unwrap();
+        }
+        let false_positives = (10000..110000).filter(|i| sbf.contains(i)).count();
+
+        assert!(sbf.false_positive_probability() < 0.01);
+        assert!((false_positives as f64 / 100000.) < 0.01);
+    }
+}
@@ -0,0 +1,263 @@
+use crate::set_membership::Membership;
+use std::borrow::Borrow;
+use std::fmt::{Debug, Formatter};
+use std::hash::{BuildHasher, Hash};
+use std::marker::PhantomData;
+use std::ops::BitXor;
+
+const MAX_ATTEMPTS: usize = 100;
+
+pub type XorFilter8<T, H> = XorFilter<T, H, u8>;
+pub type XorFilter16<T, H> = XorFilter<T, H, u16>;
+
+pub struct XorFilter<T, H, F = u8> {
+    fingerprints: Vec<F>,
+    block_length: usize,
+    seed: u64,
+    len: usize,
+    build_hasher: H,
+    _phantom: PhantomData<T>,
+}
+
+impl<T, H, F> XorFilter<T, H, F>
+where
+    T: Hash,
+    H: BuildHasher,
+    F: Fingerprint,
+{
+    pub fn from_keys<I>(keys: I, build_hasher: H) -> Result<Self, ConstructionFailed>
+    where
+        I: IntoIterator,
+        I::Item: Borrow<T>,
+    {
+        let hashes = unique_hashes(keys, &build_hasher);
+        let capacity = 32 + (1.23 * hashes.len() as f64).ceil() as usize;
+        let block_length = capacity / 3;
+
+        let (fingerprints, seed) = build(&hashes, 3 * block_length, |hash| {
+            positions(hash, block_length)
+        })?;
+        Ok(Self {
+            fingerprints,
+            block_length,
+            seed,
+            len: hashes.len(),
+            build_hasher,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+impl<T, H, F> XorFilter<T, H, F> {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn num_fingerprints(&self) -> usize {
+        self.fingerprints.len()
+    }
+}
+
+impl<T, H, F> Membership<T> for XorFilter<T, H, F>
+where
+    T: Hash,
+    H: BuildHasher,
+    F: Fingerprint,
+{
+    fn contains(&self, item: &T) -> bool {
+        let hash = mix(self.build_hasher.hash_one(item), self.seed);
+        let [h0, h1, h2] = positions(hash, self.block_length);
+        F::from_hash(hash) ^ self.fingerprints[h0] ^ self.fingerprints[h1] ^ self.fingerprints[h2]
+            == F::default()
+    }
+}
+
+impl<T, H, F> Debug for XorFilter<T, H, F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "XorFilter {{ fingerprint_size: {}, len: {}, num_fingerprints: {} }}",
+            8 * size_of::<F>(),
+            self.len,
+            self.fingerprints.len()
+        )
+    }
+}
+
+fn positions(hash: u64, block_length: usize) -> [usize; 3] {
+    [0, 1, 2].map(|i| {
+        let h = hash.rotate_left(21 * i) as u32;
+        i as usize * block_length + reduce(h, block_length)
+    })
+}
+
+fn reduce(hash: u32, n: usize) -> usize {
+    ((hash as u64 * n as u64) >> 32) as usize
+}
+
+pub trait Fingerprint: Copy + Default + Eq + BitXor<Output = Self> {
+    fn from_hash(hash: u64) -> Self;
+}
+
+macro_rules! impl_fingerprint {
+    ($t:ty) => {
+        impl Fingerprint for $t {
+            fn from_hash(hash: u64) -> Self {
+                (hash ^ (hash >> 32)) as $t
+            }
+        }
+    };
+}
+
+impl_fingerprint!(u8);
+impl_fingerprint!(u16);
+
+#[derive(Debug, Clone)]
+pub struct ConstructionFailed;
+
+impl std::fmt::Display for ConstructionFailed {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "construction failed")
+    }
+}
+
+impl std::error::Error for ConstructionFailed {}
+
+pub(crate) fn unique_hashes<T, I, H>(keys: I, build_hasher: &H) -> Vec<u64>
+where
+    T: Hash,
+    I: IntoIterator,
+    I::Item: Borrow<T>,
+    H: BuildHasher,
+{
+    // Duplicate keys would never peel, so drop them up front.
+    let mut hashes = keys
+        .into_iter()
+        .map(|key| build_hasher.hash_one(key.borrow()))
+        .collect::<Vec<_>>();
+    hashes.sort_unstable();
+    hashes.dedup();
+    hashes
+}
+
+// MurmurHash3 finalizer, used to rehash keys with a fresh seed after a failed attempt.
+pub(crate) fn mix(hash: u64, seed: u64) -> u64 {
+    let mut h = hash.wrapping_add(seed);
+    h ^= h >> 33;
+    h = h.wrapping_mul(0xff51afd7ed558ccd);
+    h ^= h >> 33;
+    h = h.wrapping_mul(0xc4ceb9fe1a85ec53);
+    h ^ (h >> 33)
+}
+
+// Peels the 3-hypergraph of keys and assigns fingerprints in reverse peeling order, so that
+// the fingerprints at the three positions of every key XOR to the key's fingerprint.
+pub(crate) fn build<F>(
+    hashes: &[u64],
+    array_length: usize,
+    positions: impl Fn(u64) -> [usize; 3],
+) -> Result<(Vec<F>, u64), ConstructionFailed>
+where
+    F: Fingerprint,
+{
+    let mut seed_state = 0x726b2b9d438b9d4d_u64;
+
+    for _ in 0..MAX_ATTEMPTS {
+        seed_state = seed_state.wrapping_add(0x9e3779b97f4a7c15);
+        let seed = mix(seed_state, 0);
+
+        let mut counts = vec![0u32; array_length];
+        let mut masks = vec![0u64; array_length];
+        for &hash in hashes {
+            let hash = mix(hash, seed);
+            for position in positions(hash) {
+                counts[position] += 1;
+                masks[position] ^= hash;
+            }
+        }
+
+        let mut queue = (0..array_length)
+            .filter(|&i| counts[i] == 1)
+            .collect::<Vec<_>>();
+        let mut stack = Vec::with_capacity(hashes.len());
+        while let Some(index) = queue.pop() {
+            if counts[index] != 1 {
+                continue;
+            }
+            let hash = masks[index];
+            stack.push((hash, index));
+            for position in positions(hash) {
+                counts[position] -= 1;
+                masks[position] ^= hash;
+                if counts[position] == 1 {
+                    queue.push(position);
+                }
+            }
+        }
+
+        if stack.len() < hashes.len() {
+            continue;
+        }
+
+        let mut fingerprints = vec![F::default(); array_length];
+        for &(hash, index) in stack.iter().rev() {
+            fingerprints[index] = positions(hash)
+                .into_iter()
+                .fold(F::from_hash(hash), |f, position| f ^ fingerprints[position]);
+        }
+        return Ok((fingerprints, seed));
+    }
+
+    Err(ConstructionFailed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::hash::{BuildHasherDefault, DefaultHasher};
+
+    type DefaultBuildHasher = BuildHasherDefault<DefaultHasher>;
+
+    #[test]
+    fn test_contains_keys() {
+        let xf = XorFilter8::<u32, _>::from_keys(0..10000, DefaultBuildHasher::default()).unwrap();
+
+        assert_eq!(xf.len(), 10000);
+        assert!((0..10000).all(|i| xf.contains(&i)));
+    }
+
+    #[test]
+    fn test_false_positive_rate() {
+        let xf8 = XorFilter8::<u32, _>::from_keys(0..10000, DefaultBuildHasher::default()).unwrap();
+        let xf16 =
+            XorFilter16::<u32, _>::from_keys(0..10000, DefaultBuildHasher::default()).unwrap();
+
+        let fp8 = (10000..110000).filter(|i| xf8.contains(i)).count();
+        let fp16 = (10000..110000).filter(|i| xf16.contains(i)).count();
+
+        assert!(fp8 < 2 * 100000 / 256);
+        assert!(fp16 < 2 * 100000 / 65536 + 5);
+    }
+
+    #[test]
+    fn test_duplicate_keys() {
+        let keys = [1u32, 2, 3, 1, 2, 3];
+        let xf = XorFilter8::<u32, _>::from_keys(keys, DefaultBuildHasher::default()).unwrap();
+
+        assert_eq!(xf.len(), 3);
+        assert!(keys.iter().all(|key| xf.contains(key)));
+    }
+
+    #[test]
+    fn test_empty() {
+        let xf =
+            XorFilter16::<u32, _>::from_keys(&[] as &[u32], DefaultBuildHasher::default()).unwrap();
+
+        assert!(xf.is_empty());
+        assert!((0..1000).filter(|i| xf.contains(i)).count() < 5);
+    }
+}
@@ -0,0 +1,275 @@
+use crate::error::{ensure, ensure_positive, ParameterError};
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Debug, Formatter};
+use std::hash::{BuildHasher, Hash};
+
+// Splits signatures into `bands` bands of `rows` values each. Two signatures become
+// candidates when they agree on every row of at least one band, which happens with
+// probability `1 - (1 - s^rows)^bands` for Jaccard similarity `s`.
+pub struct LshIndex<Id, H> {
+    bands: usize,
+    rows: usize,
+    ids: Vec<Id>,
+    positions: HashMap<Id, usize>,
+    // The band hashes of every id, `bands` per id, to take it out of its buckets again.
+    keys: Vec<u64>,
+    // One table per band, from the hash of the band to indices into `ids`.
+    buckets: Vec<HashMap<u64, Vec<usize>>>,
+    build_hasher: H,
+}
+
+impl<Id, H> LshIndex<Id, H> {
+    pub fn new(bands: usize, rows: usize, build_hasher: H) -> Self {
+        Self::try_new(bands, rows, build_hasher).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(bands: usize, rows: usize, build_hasher: H) -> Result<Self, ParameterError> {
+        ensure_positive("bands", bands)?;
+        ensure_positive("rows", rows)?;
+        bands.checked_mul(rows).ok_or(ParameterError::Overflow {
+            name: "bands * rows",
+        })?;
+        Ok(Self {
+            bands,
+            rows,
+            ids: Vec::new(),
+            positions: HashMap::new(),
+            keys: Vec::new(),
+            buckets: (0..bands).map(|_| HashMap::new()).collect(),
+            build_hasher,
+        })
+    }
+
+    pub fn with_threshold(num_hashes: usize, threshold: f64, build_hasher: H) -> Self {
+        Self::try_with_threshold(num_hashes, threshold, build_hasher)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    // Picks the banding whose S-curve midpoint `(1 / bands)^(1 / rows)` is closest to the
+    // threshold, using at most `num_hashes` signature values.
+    pub fn try_with_threshold(
+        num_hashes: usize,
+        threshold: f64,
+        build_hasher: H,
+    ) -> Result<Self, ParameterError> {
+        ensure_positive("num_hashes", num_hashes)?;
+        ensure(
+            0. < threshold && threshold < 1.,
+            ParameterError::OutOfRange {
+                name: "threshold",
+                range: "(0, 1)",
+            },
+        )?;
+        let (bands, rows) = (1..=num_hashes)
+            .map(|rows| (num_hashes / rows, rows))
+            .min_by(|&(a_bands, a_rows), &(b_bands, b_rows)| {
+                let a = (midpoint(a_bands, a_rows) - threshold).abs();
+                let b = (midpoint(b_bands, b_rows) - threshold).abs();
+                a.total_cmp(&b)
+            })
+            .unwrap();
+        Self::try_new(bands, rows, build_hasher)
+    }
+
+    pub fn bands(&self) -> usize {
+        self.bands
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+}
+
+impl<Id, H> LshIndex<Id, H>
+where
+    Id: Clone + Eq + Hash,
+    H: BuildHasher,
+{
+    // Inserting an id again replaces its signature. Panics if the signature has fewer than
+    // `bands * rows` values.
+    pub fn insert(&mut self, id: Id, signature: &[u32]) {
+        let keys = self.band_keys(signature).collect::<Vec<_>>();
+        let index = match self.positions.get(&id) {
+            Some(&index) => {
+                let old_keys = &mut self.keys[index * self.bands..(index + 1) * self.bands];
+                for (table, key) in self.buckets.iter_mut().zip(old_keys.iter()) {
+                    let bucket = table.get_mut(key).unwrap();
+                    bucket.retain(|&i| i != index);
+                    if bucket.is_empty() {
+                        table.remove(key);
+                    }
+                }
+                old_keys.copy_from_slice(&keys);
+                index
+            }
+            None => {
+                let index = self.ids.len();
+                self.positions.insert(id.clone(), index);
+                self.ids.push(id);
+                self.keys.extend(&keys);
+                index
+            }
+        };
+        for (table, key) in self.buckets.iter_mut().zip(keys) {
+            table.entry(key).or_default().push(index);
+        }
+    }
+
+    // Panics if the signature has fewer than `bands * rows` values.
+    pub fn query(&self, signature: &[u32]) -> impl Iterator<Item = Id> {
+        let mut seen = HashSet::new();
+        let mut candidates = Vec::new();
+        for (band, key) in self.band_keys(signature).enumerate() {
+            for &index in self.buckets[band].get(&key).into_iter().flatten() {
+                if seen.insert(index) {
+                    candidates.push(self.ids[index].clone());
+                }
+            }
+        }
+        candidates.into_iter()
+    }
+
+    // Every pair sharing at least one bucket, reported once in insertion order.
+    pub fn candidate_pairs(&self) -> Vec<(Id, Id)> {
+        let mut pairs = HashSet::new();
+        for table in &self.buckets {
+            for bucket in table.values() {
+                for (i, &a) in bucket.iter().enumerate() {
+                    for &b in &bucket[i + 1..] {
+                        // A replaced signature is pushed to the end of its buckets.
+                        pairs.insert((a.min(b), a.max(b)));
+                    }
+                }
+            }
+        }
+        let mut pairs = pairs.into_iter().collect::<Vec<_>>();
+        pairs.sort_unstable();
+        pairs
+            .into_iter()
+            .map(|(a, b)| (self.ids[a].clone(), self.ids[b].clone()))
+            .collect()
+    }
+
+    fn band_keys<'a>(&'a self, signature: &'a [u32]) -> impl Iterator<Item = u64> + 'a {
+        assert!(
+            signature.len() >= self.bands * self.rows,
+            "signature must have at least {} values",
+            self.bands * self.rows
+        );
+        signature
+            .chunks_exact(self.rows)
+            .take(self.bands)
+            .map(|band| self.build_hasher.hash_one(band))
+    }
+}
+
+impl<Id, H> Debug for LshIndex<Id, H> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "LshIndex {{ bands: {}, rows: {}, len: {} }}",
+            self.bands,
+            self.rows,
+            self.ids.len()
+        )
+    }
+}
+
+fn midpoint(bands: usize, rows: usize) -> f64 {
+    (1. / bands as f64).powf(1. / rows as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::similarity::minhash::MinHash;
+    use std::hash::{BuildHasherDefault, DefaultHasher};
+
+    type DefaultBuildHasher = BuildHasherDefault<DefaultHasher>;
+
+    fn signature(items: std::ops::Range<u32>) -> Vec<u32> {
+        let mut minhash = MinHash::new(128, DefaultBuildHasher::default());
+        for i in items {
+            minhash.insert(&i);
+        }
+        minhash.signature().to_vec()
+    }
+
+    #[test]
+    #[should_panic(expected = "rows must be > 0")]
+    fn test_rows_too_small() {
+        LshIndex::<u32, _>::new(16, 0, DefaultBuildHasher::default());
+    }
+
+    #[test]
+    fn test_with_threshold() {
+        let lsh = LshIndex::<u32, _>::with_threshold(128, 0.5, DefaultBuildHasher::default());
+
+        assert!(lsh.bands() * lsh.rows() <= 128);
+        assert!((midpoint(lsh.bands(), lsh.rows()) - 0.5).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_query() {
+        let mut lsh = LshIndex::new(32, 4, DefaultBuildHasher::default());
+        // Jaccard similarities with the query are 0.9, 0.29 and 0.
+        lsh.insert("near", &signature(0..1000));
+        lsh.insert("far", &signature(600..1600));
+        lsh.insert("disjoint", &signature(5000..6000));
+
+        let candidates = lsh.query(&signature(50..1050)).collect::<Vec<_>>();
+
+        assert!(candidates.contains(&"near"));
+        assert!(!candidates.contains(&"disjoint"));
+    }
+
+    #[test]
+    fn test_candidate_pairs() {
+        let mut lsh = LshIndex::new(32, 4, DefaultBuildHasher::default());
+        lsh.insert(0, &signature(0..1000));
+        lsh.insert(1, &signature(5000..6000));
+        lsh.insert(2, &signature(20..1020));
+        lsh.insert(3, &signature(5010..6010));
+
+        assert_eq!(lsh.candidate_pairs(), vec![(0, 2), (1, 3)]);
+    }
+
+    #[test]
+    fn test_insert_same_id_replaces_signature() {
+        let mut lsh = LshIndex::new(32, 4, DefaultBuildHasher::default());
+        lsh.insert(0, &signature(0..1000));
+        lsh.insert(1, &signature(5000..6000));
+        lsh.insert(0, &signature(0..1000));
+
+        assert_eq!(lsh.len(), 2);
+        assert_eq!(lsh.candidate_pairs(), vec![]);
+
+        lsh.insert(0, &signature(5020..6020));
+
+        assert_eq!(lsh.len(), 2);
+        assert_eq!(lsh.candidate_pairs(), vec![(0, 1)]);
+        assert_eq!(lsh.query(&signature(0..1000)).count(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "signature must have at least 128 values")]
+    fn test_short_signature() {
+        let mut lsh = LshIndex::new(32, 4, DefaultBuildHasher::default());
+        lsh.insert(0, &[1, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "signature must have at least 128 values")]
+    fn test_query_short_signature() {
+        let lsh = LshIndex::<u32, _>::new(32, 4, DefaultBuildHasher::default());
+        lsh.query(&[1, 2, 3]).count();
+    }
+}
@@ -0,0 +1,318 @@
+use crate::error::{ensure, ensure_positive, ParameterError};
+use crate::hash::iter_hashes;
+use crate::merge::{ensure_eq, ensure_same_hasher, IncompatibleSketch, Merge};
+use std::fmt::{Debug, Formatter};
+use std::hash::{BuildHasher, Hash};
+use std::marker::PhantomData;
+
+// Each of the `num_hashes` hashes of the `iter_hashes` family acts as one permutation, and
+// the sketch keeps the minimum value seen under each of them.
+pub struct MinHash<T, H> {
+    mins: Vec<u32>,
+    build_hasher: H,
+    _phantom: PhantomData<T>,
+}
+
+impl<T, H> MinHash<T, H> {
+    pub fn new(num_hashes: usize, build_hasher: H) -> Self {
+        Self::try_new(num_hashes, build_hasher).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(num_hashes: usize, build_hasher: H) -> Result<Self, ParameterError> {
+        ensure_positive("num_hashes", num_hashes)?;
+        Ok(Self {
+            mins: vec![u32::MAX; num_hashes],
+            build_hasher,
+            _phantom: PhantomData,
+        })
+    }
+
+    pub fn num_hashes(&self) -> usize {
+        self.mins.len()
+    }
+
+    pub fn signature(&self) -> &[u32] {
+        &self.mins
+    }
+
+    // No item has been inserted, as no hash is ever larger than the initial minimum.
+    pub fn is_empty(&self) -> bool {
+        self.mins.iter().all(|&min| min == u32::MAX)
+    }
+
+    // Li and König, "b-Bit Minwise Hashing". Keeps only the lowest `bits` bits of every
+    // minimum, which must be a power of two no larger than 32.
+    pub fn compress(&self, bits: usize) -> BBitSignature {
+        self.try_compress(bits)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_compress(&self, bits: usize) -> Result<BBitSignature, ParameterError> {
+        ensure(
+            bits.is_power_of_two(),
+            ParameterError::NotPowerOfTwo { name: "bits" },
+        )?;
+        ensure(
+            bits <= 32,
+            ParameterError::OutOfRange {
+                name: "bits",
+                range: "[1, 32]",
+            },
+        )?;
+        let per_word = 64 / bits;
+        let mask = (1u64 << bits) - 1;
+        let words = self
+            .mins
+            .chunks(per_word)
+            .map(|chunk| {
+                chunk.iter().enumerate().fold(0, |word, (i, &min)| {
+                    word | ((min as u64 & mask) << (i * bits))
+                })
+            })
+            .collect();
+        Ok(BBitSignature {
+            words,
+            bits,
+            len: self.mins.len(),
+            of_empty_set: self.is_empty(),
+        })
+    }
+}
+
+impl<T, H> MinHash<T, H>
+where
+    T: Hash,
+    H: BuildHasher,
+{
+    pub fn insert(&mut self, item: &T) {
+        for (min, hash) in self
+            .mins
+            .iter_mut()
+            .zip(iter_hashes(item, &self.build_hasher))
+        {
+            *min = (*min).min(hash);
+        }
+    }
+
+    // The similarity of two empty sets is defined as 0.
+    pub fn jaccard(&self, other: &Self) -> Result<f64, IncompatibleSketch> {
+        self.ensure_compatible(other)?;
+        if self.is_empty() && other.is_empty() {
+            return Ok(0.);
+        }
+        let matches = self
+            .mins
+            .iter()
+            .zip(&other.mins)
+            .filter(|(a, b)| a == b)
+            .count();
+        Ok(matches as f64 / self.mins.len() as f64)
+    }
+
+    fn ensure_compatible(&self, other: &Self) -> Result<(), IncompatibleSketch> {
+        ensure_eq(
+            self.mins.len(),
+            other.mins.len(),
+            IncompatibleSketch::NumHashes,
+        )?;
+        ensure_same_hasher(&self.build_hasher, &other.build_hasher)
+    }
+}
+
+impl<T, H> Merge for MinHash<T, H>
+where
+    T: Hash,
+    H: BuildHasher,
+{
+    fn merge(&mut self, other: &Self) -> Result<(), IncompatibleSketch> {
+        self.ensure_compatible(other)?;
+        for (min, &other) in self.mins.iter_mut().zip(&other.mins) {
+            *min = (*min).min(other);
+        }
+        Ok(())
+    }
+}
+
+impl<T, H> Debug for MinHash<T, H> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "MinHash {{ num_hashes: {} }}", self.mins.len())
+    }
+}
+
+// Signature values are packed `64 / bits` to a word, so a value never spans two words.
+#[derive(Clone, PartialEq, Eq)]
+pub struct BBitSignature {
+    words: Vec<u64>,
+    bits: usize,
+    len: usize,
+    of_empty_set: bool,
+}
+
+impl BBitSignature {
+    pub fn bits(&self) -> usize {
+        self.bits
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn as_words(&self) -> &[u64] {
+        &self.words
+    }
+
+    pub fn get(&self, index: usize) -> u32 {
+        assert!(index < self.len, "index out of bounds");
+        let per_word = 64 / self.bits;
+        let shift = (index % per_word) * self.bits;
+        ((self.words[index / per_word] >> shift) & ((1 << self.bits) - 1)) as u32
+    }
+
+    // Unrelated minima still agree on their lowest bits with probability about `2^-bits`,
+    // which is subtracted out of the raw match rate. As for `MinHash`, the similarity of two
+    // empty sets is 0.
+    pub fn jaccard(&self, other: &Self) -> Result<f64, IncompatibleSketch> {
+        ensure_eq(self.len, other.len, IncompatibleSketch::NumHashes)?;
+        ensure_eq(self.bits, other.bits, IncompatibleSketch::SignatureBits)?;
+        if self.of_empty_set && other.of_empty_set {
+            return Ok(0.);
+        }
+        let matches = (0..self.len)
+            .filter(|&i| self.get(i) == other.get(i))
+            .count();
+        let collision = 1. / (1u64 << self.bits) as f64;
+        let estimate = (matches as f64 / self.len as f64 - collision) / (1. - collision);
+        Ok(estimate.max(0.))
+    }
+}
+
+impl Debug for BBitSignature {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "BBitSignature {{ bits: {}, len: {} }}",
+            self.bits, self.len
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::hash::{BuildHasherDefault, DefaultHasher};
+
+    type DefaultMinHash = MinHash<u32, BuildHasherDefault<DefaultHasher>>;
+
+    fn make_minhash(num_hashes: usize) -> DefaultMinHash {
+        MinHash::new(num_hashes, BuildHasherDefault::default())
+    }
+
+    // Two sets of 1000 items sharing 500, so the Jaccard similarity is 1/3.
+    fn make_pair(num_hashes: usize) -> (DefaultMinHash, DefaultMinHash) {
+        let mut a = make_minhash(num_hashes);
+        let mut b = make_minhash(num_hashes);
+        for i in 0..1000 {
+            a.insert(&i);
+            b.insert(&(i + 500));
+        }
+        (a, b)
+    }
+
+    #[test]
+    #[should_panic(expected = "num_hashes must be > 0")]
+    fn test_num_hashes_too_small() {
+        make_minhash(0);
+    }
+
+    #[test]
+    fn test_jaccard() {
+        let (a, b) = make_pair(512);
+
+        assert!((a.jaccard(&b).unwrap() - 1. / 3.).abs() < 0.07);
+        assert_eq!(a.jaccard(&a), Ok(1.));
+    }
+
+    #[test]
+    fn test_merge_matches_union() {
+        let (mut a, b) = make_pair(128);
+        let mut union = make_minhash(128);
+        for i in 0..1500 {
+            union.insert(&i);
+        }
+
+        a.merge(&b).unwrap();
+
+        assert_eq!(a.signature(), union.signature());
+    }
+
+    #[test]
+    fn test_merge_incompatible() {
+        let mut a = make_minhash(128);
+
+        assert_eq!(
+            a.merge(&make_minhash(64)),
+            Err(IncompatibleSketch::NumHashes(128, 64))
+        );
+    }
+
+    #[test]
+    fn test_jaccard_incompatible() {
+        assert_eq!(
+            make_minhash(128).jaccard(&make_minhash(64)),
+            Err(IncompatibleSketch::NumHashes(128, 64))
+        );
+    }
+
+    #[test]
+    fn test_jaccard_empty() {
+        let empty = make_minhash(128);
+        let (a, _) = make_pair(128);
+
+        assert!(empty.is_empty());
+        assert_eq!(empty.jaccard(&make_minhash(128)), Ok(0.));
+        assert_eq!(empty.jaccard(&a), Ok(0.));
+        assert_eq!(empty.compress(4).jaccard(&empty.compress(4)), Ok(0.));
+    }
+
+    #[test]
+    fn test_compress() {
+        let (a, b) = make_pair(512);
+
+        for bits in [1, 2, 4, 8, 16, 32] {
+            let a_compressed = a.compress(bits);
+            let b_compressed = b.compress(bits);
+
+            assert_eq!(a_compressed.len(), 512);
+            assert_eq!(a_compressed.as_words().len(), (512 * bits).div_ceil(64));
+            assert_eq!(
+                a_compressed.get(7),
+                a.signature()[7] & ((1u64 << bits) - 1) as u32
+            );
+            assert!(
+                (a_compressed.jaccard(&b_compressed).unwrap() - 1. / 3.).abs() < 0.12,
+                "bits {}",
+                bits
+            );
+        }
+    }
+
+    #[test]
+    fn test_compressed_jaccard_incompatible() {
+        let (a, b) = make_pair(64);
+
+        assert_eq!(
+            a.compress(4).jaccard(&b.compress(8)),
+            Err(IncompatibleSketch::SignatureBits(4, 8))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "bits must be a power of two")]
+    fn test_compress_bits_not_power_of_two() {
+        make_minhash(16).compress(3);
+    }
+}
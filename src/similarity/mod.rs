@@ -0,0 +1,3 @@
+pub mod lsh;
+pub mod minhash;
+pub mod simhash;
@@ -0,0 +1,263 @@
+use crate::error::{ensure, ParameterError};
+use crate::merge::{ensure_same_hasher, IncompatibleSketch, Merge};
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Debug, Formatter};
+use std::hash::{BuildHasher, Hash};
+use std::marker::PhantomData;
+
+// Charikar's SimHash. Every feature adds its weight to the bits set in its hash and
+// subtracts it from the others; the fingerprint keeps the sign of each sum.
+pub struct SimHash<T, H> {
+    sums: [f64; 64],
+    build_hasher: H,
+    _phantom: PhantomData<T>,
+}
+
+impl<T, H> SimHash<T, H> {
+    pub fn new(build_hasher: H) -> Self {
+        Self {
+            sums: [0.; 64],
+            build_hasher,
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn fingerprint(&self) -> u64 {
+        self.sums
+            .iter()
+            .enumerate()
+            .filter(|(_, &sum)| sum > 0.)
+            .fold(0, |fingerprint, (i, _)| fingerprint | (1 << i))
+    }
+
+    pub fn distance(&self, other: &Self) -> u32 {
+        hamming_distance(self.fingerprint(), other.fingerprint())
+    }
+}
+
+impl<T, H> SimHash<T, H>
+where
+    T: Hash,
+    H: BuildHasher,
+{
+    pub fn insert(&mut self, item: &T, weight: f64) {
+        let hash = self.build_hasher.hash_one(item);
+        for (i, sum) in self.sums.iter_mut().enumerate() {
+            if hash & (1 << i) != 0 {
+                *sum += weight;
+            } else {
+                *sum -= weight;
+            }
+        }
+    }
+}
+
+impl<T, H> Merge for SimHash<T, H>
+where
+    H: BuildHasher,
+{
+    fn merge(&mut self, other: &Self) -> Result<(), IncompatibleSketch> {
+        ensure_same_hasher(&self.build_hasher, &other.build_hasher)?;
+        for (sum, other) in self.sums.iter_mut().zip(other.sums) {
+            *sum += other;
+        }
+        Ok(())
+    }
+}
+
+impl<T, H> Debug for SimHash<T, H> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SimHash {{ fingerprint: {:#018x} }}", self.fingerprint())
+    }
+}
+
+pub fn hamming_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+// Manku et al., "Detecting Near-Duplicates for Web Crawling". The 64 bits are split into
+// `max_distance + 1` blocks, and each table is keyed on one block, as if the fingerprints
+// were permuted to bring that block to the top. Fingerprints within `max_distance` bits
+// must agree exactly on at least one block, so only those buckets need to be checked.
+pub struct SimHashIndex<Id> {
+    max_distance: u32,
+    fingerprints: Vec<(u64, Id)>,
+    masks: Vec<u64>,
+    tables: Vec<HashMap<u64, Vec<usize>>>,
+}
+
+impl<Id> SimHashIndex<Id> {
+    pub fn new(max_distance: u32) -> Self {
+        Self::try_new(max_distance).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(max_distance: u32) -> Result<Self, ParameterError> {
+        ensure(
+            max_distance < 64,
+            ParameterError::OutOfRange {
+                name: "max_distance",
+                range: "[0, 63]",
+            },
+        )?;
+        let num_blocks = max_distance as usize + 1;
+        let masks = (0..num_blocks)
+            .map(|i| {
+                let start = 64 * i / num_blocks;
+                let end = 64 * (i + 1) / num_blocks;
+                (u64::MAX >> (64 - (end - start))) << start
+            })
+            .collect();
+        Ok(Self {
+            max_distance,
+            fingerprints: Vec::new(),
+            masks,
+            tables: (0..num_blocks).map(|_| HashMap::new()).collect(),
+        })
+    }
+
+    pub fn max_distance(&self) -> u32 {
+        self.max_distance
+    }
+
+    pub fn len(&self) -> usize {
+        self.fingerprints.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fingerprints.is_empty()
+    }
+
+    pub fn insert(&mut self, id: Id, fingerprint: u64) {
+        let index = self.fingerprints.len();
+        for (table, mask) in self.tables.iter_mut().zip(&self.masks) {
+            table.entry(fingerprint & mask).or_default().push(index);
+        }
+        self.fingerprints.push((fingerprint, id));
+    }
+}
+
+impl<Id> SimHashIndex<Id>
+where
+    Id: Clone,
+{
+    pub fn query(&self, fingerprint: u64) -> impl Iterator<Item = Id> {
+        let mut seen = HashSet::new();
+        let mut matches = Vec::new();
+        for (table, mask) in self.tables.iter().zip(&self.masks) {
+            for &index in table.get(&(fingerprint & mask)).into_iter().flatten() {
+                let (candidate, id) = &self.fingerprints[index];
+                if seen.insert(index)
+                    && hamming_distance(fingerprint, *candidate) <= self.max_distance
+                {
+                    matches.push(id.clone());
+                }
+            }
+        }
+        matches.into_iter()
+    }
+}
+
+impl<Id> Debug for SimHashIndex<Id> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "SimHashIndex {{ max_distance: {}, len: {} }}",
+            self.max_distance,
+            self.fingerprints.len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use std::hash::{BuildHasherDefault, DefaultHasher};
+
+    type DefaultSimHash = SimHash<&'static str, BuildHasherDefault<DefaultHasher>>;
+
+    fn make_simhash(text: &'static str) -> DefaultSimHash {
+        let mut simhash = SimHash::new(BuildHasherDefault::default());
+        for word in text.split_whitespace() {
+            simhash.insert(&word, 1.);
+        }
+        simhash
+    }
+
+    #[test]
+    fn test_near_duplicates() {
+        let a = make_simhash(
+            "the quick brown fox jumps over the lazy dog while the cat sleeps on the warm \
+             mat next to the old fireplace in the small cottage by the river",
+        );
+        let b = make_simhash(
+            "the quick brown fox jumps over the lazy dog while the cat sleeps on the warm \
+             rug next to the old fireplace in the small cottage by the river",
+        );
+        let c = make_simhash(
+            "stock markets rallied today as investors welcomed news of lower inflation and \
+             strong earnings from several large technology companies",
+        );
+
+        assert!(a.distance(&b) < 10);
+        assert!(a.distance(&c) > 15);
+    }
+
+    #[test]
+    fn test_weights() {
+        let mut a = DefaultSimHash::new(BuildHasherDefault::default());
+        a.insert(&"heavy", 10.);
+        a.insert(&"light", 1.);
+
+        assert_eq!(
+            a.fingerprint(),
+            BuildHasherDefault::<DefaultHasher>::default().hash_one("heavy")
+        );
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut a = make_simhash("one two three");
+        let b = make_simhash("four five six");
+
+        a.merge(&b).unwrap();
+
+        assert_eq!(
+            a.fingerprint(),
+            make_simhash("one two three four five six").fingerprint()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "max_distance must be in the range [0, 63]")]
+    fn test_max_distance_too_large() {
+        SimHashIndex::<u32>::new(64);
+    }
+
+    #[test]
+    fn test_index_matches_linear_scan() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut index = SimHashIndex::new(3);
+        let mut fingerprints = Vec::new();
+        let base = rng.gen::<u64>();
+        for id in 0..2000 {
+            let fingerprint = if id % 2 == 0 {
+                (0..rng.gen_range(0..6)).fold(base, |f, _| f ^ (1 << rng.gen_range(0..64)))
+            } else {
+                rng.gen()
+            };
+            index.insert(id, fingerprint);
+            fingerprints.push(fingerprint);
+        }
+
+        let mut found = index.query(base).collect::<Vec<_>>();
+        found.sort_unstable();
+        let expected = (0..2000)
+            .filter(|&id| hamming_distance(base, fingerprints[id]) <= 3)
+            .collect::<Vec<_>>();
+
+        assert!(!expected.is_empty());
+        assert_eq!(found, expected);
+    }
+}